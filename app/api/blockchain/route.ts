@@ -27,13 +27,20 @@ const transferTokensSchema = z.object({
 })
 
 // Helper function to call Rust service
-async function callRustService(endpoint: string, method: string = 'GET', body?: any) {
+async function callRustService(
+  request: NextRequest,
+  endpoint: string,
+  method: string = 'GET',
+  body?: any
+) {
   const url = `${RUST_SERVICE_URL}${endpoint}`
+  const userToken = request.headers.get('authorization')?.substring(7)
   const options: RequestInit = {
     method,
     headers: {
       'Content-Type': 'application/json',
-      ...(RUST_SERVICE_API_KEY && { 'Authorization': `Bearer ${RUST_SERVICE_API_KEY}` })
+      ...(RUST_SERVICE_API_KEY && { 'Authorization': `Bearer ${RUST_SERVICE_API_KEY}` }),
+      ...(userToken && { 'X-Supabase-Token': userToken })
     },
   }
 
@@ -61,6 +68,7 @@ export const GET = asyncHandler(async (request: NextRequest) => {
   const offset = parseInt(url.searchParams.get('offset') || '0')
 
   const response = await callRustService(
+    request,
     `/transactions?user_id=${user.id}&limit=${limit}&offset=${offset}`
   )
 
@@ -78,7 +86,7 @@ export const POST = asyncHandler(async (request: NextRequest) => {
   )
 
   // Call Rust service to mint tokens
-  const response = await callRustService('/mint/tokens', 'POST', {
+  const response = await callRustService(request, '/mint/tokens', 'POST', {
     user_id: user.id,
     mint_address: mintAddress,
     destination_address: destinationAddress,
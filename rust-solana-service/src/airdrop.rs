@@ -0,0 +1,387 @@
+use crate::{
+    amount::TokenAmount,
+    blockchain::{env_parse, BroadcastOutcome, BuiltTransaction},
+    error::{AppError, Result},
+    base_record,
+    models::{AirdropJob, AirdropRecipientRecord, AirdropRetry, PriorityFeeOptions, TransactionRecord},
+    AppState,
+};
+use anyhow::anyhow;
+use solana_sdk::pubkey::Pubkey;
+use std::{
+    collections::HashSet,
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::{sync::Semaphore, task::JoinSet};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+pub struct AirdropConfig {
+    // Transactions of one job in flight at a time
+    pub concurrency: usize,
+    // Upper bound on recipients per transaction; fewer are packed when the
+    // transaction would not fit in a packet
+    pub max_batch_recipients: usize,
+    // Failed transactions a recipient is retried in before it is given up on
+    pub max_attempts: i32,
+    pub poll_interval: Duration,
+}
+
+impl AirdropConfig {
+    pub fn from_env() -> Self {
+        let env_or = |name: &str, default: usize| {
+            env_parse(name).filter(|value| *value > 0).unwrap_or(default)
+        };
+
+        Self {
+            concurrency: env_or("AIRDROP_CONCURRENCY", 4),
+            max_batch_recipients: env_or("AIRDROP_MAX_BATCH_RECIPIENTS", 16),
+            max_attempts: env_or("AIRDROP_MAX_ATTEMPTS", 3) as i32,
+            poll_interval: Duration::from_secs(env_or("AIRDROP_POLL_INTERVAL_SECS", 5) as u64),
+        }
+    }
+}
+
+// Runs airdrop jobs in the background. Progress lives in Postgres, so the
+// only state here is which jobs this process is already running.
+pub struct AirdropRunner {
+    config: AirdropConfig,
+    running: Mutex<HashSet<Uuid>>,
+}
+
+impl AirdropRunner {
+    pub fn new(config: AirdropConfig) -> Self {
+        Self {
+            config,
+            running: Mutex::new(HashSet::new()),
+        }
+    }
+}
+
+// Starts running a job unless this process already is. A run that hits an
+// error starts over after the poll interval, picking up where it stopped.
+pub fn start(state: &AppState, job_id: Uuid) {
+    if !state.airdrops.running.lock().unwrap().insert(job_id) {
+        return;
+    }
+
+    let state = state.clone();
+    tokio::spawn(async move {
+        while let Err(err) = run_job(&state, job_id).await {
+            warn!("Airdrop job {} interrupted: {:?}", job_id, err);
+            tokio::time::sleep(state.airdrops.config.poll_interval).await;
+        }
+
+        state.airdrops.running.lock().unwrap().remove(&job_id);
+    });
+}
+
+// Resumes the jobs a previous run of the service left unfinished
+pub fn resume(state: AppState) {
+    tokio::spawn(async move {
+        match state.database.get_running_airdrop_jobs().await {
+            Ok(job_ids) => {
+                if !job_ids.is_empty() {
+                    info!("Resuming {} airdrop jobs", job_ids.len());
+                }
+                for job_id in job_ids {
+                    start(&state, job_id);
+                }
+            }
+            Err(err) => warn!("Failed to load airdrop jobs to resume: {:?}", err),
+        }
+    });
+}
+
+async fn run_job(state: &AppState, job_id: Uuid) -> Result<()> {
+    let config = &state.airdrops.config;
+
+    let job = Arc::new(
+        state
+            .database
+            .get_airdrop_job(job_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Airdrop job {} not found", job_id)))?,
+    );
+
+    if job.status != "running" {
+        return Ok(());
+    }
+
+    let mint = Pubkey::from_str(&job.mint_address)
+        .map_err(|_| AppError::Internal(format!("Airdrop job {} has an invalid mint", job_id)))?;
+    let fees: PriorityFeeOptions = serde_json::from_value(job.fees.clone()).unwrap_or_default();
+
+    loop {
+        let in_flight = settle_in_flight(state, job_id).await?;
+
+        let pending = state
+            .database
+            .get_pending_airdrop_recipients(job_id, (config.concurrency * config.max_batch_recipients) as i64)
+            .await?;
+
+        if pending.is_empty() {
+            if in_flight == 0 && state.database.complete_airdrop_job(job_id).await? {
+                info!("Airdrop job {} completed", job_id);
+                return Ok(());
+            }
+
+            // Waiting on transactions the reconciler has yet to settle
+            tokio::time::sleep(config.poll_interval).await;
+            continue;
+        }
+
+        // Recipients whose transaction already failed are retried alone, so
+        // a recipient the mint cannot reach does not keep failing the others
+        let (retries, fresh): (Vec<_>, Vec<_>) = pending.into_iter().partition(|recipient| recipient.attempts > 0);
+        let batches = fresh
+            .chunks(config.max_batch_recipients)
+            .map(<[AirdropRecipientRecord]>::to_vec)
+            .chain(retries.into_iter().map(|recipient| vec![recipient]));
+
+        let semaphore = Arc::new(Semaphore::new(config.concurrency));
+        let mut tasks = JoinSet::new();
+
+        for batch in batches {
+            let permit = semaphore
+                .clone()
+                .acquire_owned()
+                .await
+                .map_err(|e| anyhow!("Airdrop semaphore closed: {}", e))?;
+            let state = state.clone();
+            let job = job.clone();
+            let fees = fees.clone();
+
+            tasks.spawn(async move {
+                let _permit = permit;
+                send_batch(&state, &job, &mint, batch, &fees).await
+            });
+        }
+
+        let mut interrupted = false;
+        while let Some(result) = tasks.join_next().await {
+            let err = match result {
+                Ok(Ok(())) => continue,
+                Ok(Err(err)) => err,
+                Err(err) => AppError::Internal(format!("Airdrop batch task failed: {}", err)),
+            };
+
+            warn!("Airdrop batch for job {} did not settle: {:?}", job_id, err);
+            interrupted = true;
+        }
+
+        // Back off rather than spin while the cluster or RPC node is failing
+        if interrupted {
+            tokio::time::sleep(config.poll_interval).await;
+        }
+    }
+}
+
+// Settles recipients whose transaction was left unsettled, e.g. by a crash
+// or an RPC error mid-broadcast, from the row the reconciler maintains.
+// Returns how many transactions are still undecided.
+async fn settle_in_flight(state: &AppState, job_id: Uuid) -> Result<usize> {
+    let mut undecided = 0;
+
+    for (transaction_hash, status) in state.database.get_in_flight_airdrop_transactions(job_id).await? {
+        match status.as_deref() {
+            Some("confirmed") => state.database.confirm_airdrop_transaction(&transaction_hash).await?,
+            Some("failed") => {
+                state
+                    .database
+                    .retry_airdrop_recipients(
+                        AirdropRetry::InFlight(&transaction_hash),
+                        "Transaction failed or expired",
+                        true,
+                        state.airdrops.config.max_attempts,
+                    )
+                    .await?
+            }
+            // Recorded before sending, so without a row it was never sent
+            None => {
+                state
+                    .database
+                    .retry_airdrop_recipients(
+                        AirdropRetry::InFlight(&transaction_hash),
+                        "Transaction was never sent",
+                        false,
+                        state.airdrops.config.max_attempts,
+                    )
+                    .await?
+            }
+            Some(_) => undecided += 1,
+        }
+    }
+
+    Ok(undecided)
+}
+
+// Sends one transaction to the head of `batch`; recipients that did not fit
+// stay pending for the next round
+async fn send_batch(
+    state: &AppState,
+    job: &AirdropJob,
+    mint: &Pubkey,
+    batch: Vec<AirdropRecipientRecord>,
+    fees: &PriorityFeeOptions,
+) -> Result<()> {
+    let max_attempts = state.airdrops.config.max_attempts;
+
+    let built = async {
+        let entries = batch
+            .iter()
+            .map(|recipient| {
+                let destination = Pubkey::from_str(&recipient.destination).map_err(|_| {
+                    AppError::Internal(format!("Invalid airdrop destination {}", recipient.destination))
+                })?;
+                Ok((destination, recipient.amount))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        state.blockchain.build_batch_mint(mint, &entries, fees).await
+    }.await;
+
+    // A batch that cannot be built would otherwise be retried forever, so
+    // each failure counts as an attempt; a failed batch is then retried one
+    // recipient at a time like any other
+    let (built, packed) = match built {
+        Ok(built) => built,
+        Err(err) => {
+            let recipient_ids: Vec<Uuid> = batch.iter().map(|recipient| recipient.id).collect();
+            let error = format!("Transaction could not be built: {}", err);
+            state
+                .database
+                .retry_airdrop_recipients(
+                    AirdropRetry::Unbuilt(&recipient_ids),
+                    &error,
+                    true,
+                    max_attempts,
+                )
+                .await?;
+            return Err(err);
+        }
+    };
+    let batch = &batch[..packed];
+    let signature = built.signature();
+
+    let record = airdrop_transaction_record(job, mint, batch, &built)?;
+    state.database.store_transaction(&record).await?;
+
+    let recipient_ids: Vec<Uuid> = batch.iter().map(|recipient| recipient.id).collect();
+    let marked = state.database.mark_airdrop_sending(&recipient_ids, &signature).await?;
+    if marked != recipient_ids.len() as u64 {
+        // Never sent, so the reconciler expires the row and any recipients
+        // that did get marked are requeued from it
+        return Err(AppError::Conflict(format!(
+            "Airdrop recipients of job {} were already taken",
+            job.id
+        )));
+    }
+
+    // An RPC error leaves the recipients in flight until the reconciler has
+    // a definite answer for the transaction
+    let outcome = match state.blockchain.broadcast_until_expired(&built).await {
+        Ok(outcome) => outcome,
+        // Rejected in preflight, so it never reached the cluster
+        Err(AppError::TransactionFailed(reason)) => {
+            state
+                .database
+                .record_transaction_outcome(
+                    &signature,
+                    "failed",
+                    None,
+                    serde_json::json!({ "failure_reason": "preflight_failed", "error": reason }),
+                )
+                .await?;
+            state
+                .database
+                .retry_airdrop_recipients(
+                    AirdropRetry::InFlight(&signature),
+                    &reason,
+                    true,
+                    max_attempts,
+                )
+                .await?;
+            return Ok(());
+        }
+        Err(err) => return Err(err),
+    };
+
+    match outcome {
+        BroadcastOutcome::Confirmed { slot } => {
+            state
+                .database
+                .record_transaction_outcome(&signature, "confirmed", Some(slot), serde_json::json!({}))
+                .await?;
+            state.database.confirm_airdrop_transaction(&signature).await?;
+
+            info!("Airdrop job {} minted to {} recipients in {}", job.id, packed, signature);
+        }
+        BroadcastOutcome::Failed { slot } => {
+            state
+                .database
+                .record_transaction_outcome(&signature, "failed", slot, serde_json::json!({}))
+                .await?;
+            state
+                .database
+                .retry_airdrop_recipients(
+                    AirdropRetry::InFlight(&signature),
+                    "Transaction failed on-chain",
+                    true,
+                    max_attempts,
+                )
+                .await?;
+        }
+        // Expiry says nothing about the recipients, so it is not an attempt
+        BroadcastOutcome::Expired => {
+            state
+                .database
+                .record_transaction_outcome(
+                    &signature,
+                    "failed",
+                    None,
+                    serde_json::json!({ "failure_reason": "blockhash_expired" }),
+                )
+                .await?;
+            state
+                .database
+                .retry_airdrop_recipients(
+                    AirdropRetry::InFlight(&signature),
+                    "Transaction expired",
+                    false,
+                    max_attempts,
+                )
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+// One row per batch transaction, recorded against the admin who created the
+// job. `amount` is the batch total, which confirmation adds to the supply.
+fn airdrop_transaction_record(
+    job: &AirdropJob,
+    mint: &Pubkey,
+    batch: &[AirdropRecipientRecord],
+    built: &BuiltTransaction,
+) -> Result<TransactionRecord> {
+    let decimals = batch[0].amount.decimals();
+    let raw = batch
+        .iter()
+        .try_fold(0u64, |total, recipient| total.checked_add(recipient.amount.raw()))
+        .ok_or_else(|| AppError::Internal("Airdrop batch total overflows".to_string()))?;
+    let amount = TokenAmount::new(raw, decimals);
+
+    Ok(TransactionRecord {
+        amount: Some(amount),
+        token_address: Some(mint.to_string()),
+        ..base_record(built, job.created_by, "mint", serde_json::json!({
+            "airdrop_job_id": job.id,
+            "recipients": batch.len(),
+            "amount": amount.raw()
+        }))
+    })
+}
@@ -0,0 +1,267 @@
+use crate::error::{AppError, Result};
+use bigdecimal::BigDecimal;
+use serde::{Deserialize, Serialize};
+use sqlx::{
+    encode::IsNull,
+    error::BoxDynError,
+    postgres::{PgArgumentBuffer, PgTypeInfo, PgValueRef},
+    Decode, Encode, Postgres, Type,
+};
+use std::{fmt, str::FromStr};
+
+// Lamports are SOL's base units
+pub const SOL_DECIMALS: u8 = 9;
+
+// A token amount in raw base units together with the mint's decimals, so it
+// can be shown in UI units without ever going through a float.
+//
+// Serialized as `{ "amount": "<raw>", "decimals": n, "ui_amount": "<ui>" }`;
+// the raw amount is a string because JSON numbers lose precision past 2^53.
+// In Postgres it is stored as `NUMERIC` in UI units with a scale equal to the
+// mint's decimals, which `NUMERIC` preserves, so it decodes back unchanged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(into = "TokenAmountRepr", try_from = "TokenAmountRepr")]
+pub struct TokenAmount {
+    raw: u64,
+    decimals: u8,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenAmountRepr {
+    amount: String,
+    decimals: u8,
+    #[serde(default, skip_deserializing)]
+    ui_amount: String,
+}
+
+impl TokenAmount {
+    pub fn new(raw: u64, decimals: u8) -> Self {
+        Self { raw, decimals }
+    }
+
+    pub fn lamports(lamports: u64) -> Self {
+        Self::new(lamports, SOL_DECIMALS)
+    }
+
+    pub fn raw(&self) -> u64 {
+        self.raw
+    }
+
+    pub fn decimals(&self) -> u8 {
+        self.decimals
+    }
+
+    // Parses a UI amount such as "12.5". More fractional digits than the mint
+    // has decimals would need rounding, so they are rejected instead.
+    pub fn from_ui_str(value: &str, decimals: u8) -> Result<Self> {
+        let value = value.trim();
+        let (whole, fraction) = value.split_once('.').unwrap_or((value, ""));
+
+        let is_digits = |part: &str| part.bytes().all(|byte| byte.is_ascii_digit());
+        if (whole.is_empty() && fraction.is_empty()) || !is_digits(whole) || !is_digits(fraction) {
+            return Err(AppError::InvalidInput(format!("Invalid token amount: {}", value)));
+        }
+
+        let fraction = fraction.trim_end_matches('0');
+        if fraction.len() > decimals as usize {
+            return Err(AppError::InvalidInput(format!(
+                "Amount {} has more than {} decimal places",
+                value, decimals
+            )));
+        }
+
+        let digits = format!("{}{:0<width$}", whole, fraction, width = decimals as usize);
+        let raw = digits
+            .parse::<u64>()
+            .map_err(|_| AppError::InvalidInput(format!("Token amount {} is too large", value)))?;
+
+        Ok(Self::new(raw, decimals))
+    }
+
+    pub fn to_ui_string(&self) -> String {
+        if self.decimals == 0 {
+            return self.raw.to_string();
+        }
+
+        let digits = format!("{:0>width$}", self.raw, width = self.decimals as usize + 1);
+        let (whole, fraction) = digits.split_at(digits.len() - self.decimals as usize);
+        let fraction = fraction.trim_end_matches('0');
+
+        if fraction.is_empty() {
+            whole.to_string()
+        } else {
+            format!("{}.{}", whole, fraction)
+        }
+    }
+
+    pub fn to_decimal(&self) -> BigDecimal {
+        BigDecimal::from_str(&self.to_ui_string())
+            .expect("UI amount is a valid decimal")
+            .with_scale(self.decimals as i64)
+    }
+
+    // The scale of `value` is taken as the mint's decimals
+    pub fn from_decimal(value: &BigDecimal) -> Result<Self> {
+        let (_, scale) = value.as_bigint_and_exponent();
+        let decimals = u8::try_from(scale.max(0))
+            .map_err(|_| AppError::Internal(format!("Unsupported token amount scale: {}", scale)))?;
+
+        Self::from_decimal_with_scale(value, decimals)
+    }
+
+    // Rescales `value` to `decimals`, failing rather than rounding
+    pub fn from_decimal_with_scale(value: &BigDecimal, decimals: u8) -> Result<Self> {
+        let rescaled = value.with_scale(decimals as i64);
+        if &rescaled != value {
+            return Err(AppError::Internal(format!(
+                "Token amount {} does not fit {} decimals",
+                value, decimals
+            )));
+        }
+
+        let (digits, _) = rescaled.as_bigint_and_exponent();
+        let raw = digits
+            .to_string()
+            .parse::<u64>()
+            .map_err(|_| AppError::Internal(format!("Token amount {} is out of range", value)))?;
+
+        Ok(Self::new(raw, decimals))
+    }
+}
+
+impl fmt::Display for TokenAmount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_ui_string())
+    }
+}
+
+impl From<TokenAmount> for TokenAmountRepr {
+    fn from(amount: TokenAmount) -> Self {
+        Self {
+            amount: amount.raw.to_string(),
+            decimals: amount.decimals,
+            ui_amount: amount.to_ui_string(),
+        }
+    }
+}
+
+impl TryFrom<TokenAmountRepr> for TokenAmount {
+    type Error = String;
+
+    fn try_from(repr: TokenAmountRepr) -> std::result::Result<Self, Self::Error> {
+        let raw = repr
+            .amount
+            .parse()
+            .map_err(|_| format!("Invalid raw token amount: {}", repr.amount))?;
+
+        Ok(Self::new(raw, repr.decimals))
+    }
+}
+
+impl Type<Postgres> for TokenAmount {
+    fn type_info() -> PgTypeInfo {
+        <BigDecimal as Type<Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        <BigDecimal as Type<Postgres>>::compatible(ty)
+    }
+}
+
+impl Encode<'_, Postgres> for TokenAmount {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+        <BigDecimal as Encode<Postgres>>::encode(self.to_decimal(), buf)
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for TokenAmount {
+    fn decode(value: PgValueRef<'r>) -> std::result::Result<Self, BoxDynError> {
+        let decimal = <BigDecimal as Decode<Postgres>>::decode(value)?;
+        Self::from_decimal(&decimal).map_err(|e| format!("{:?}", e).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decimal(value: &str) -> BigDecimal {
+        BigDecimal::from_str(value).unwrap()
+    }
+
+    #[test]
+    fn parses_ui_amounts_exactly() {
+        assert_eq!(TokenAmount::from_ui_str("12.5", 6).unwrap(), TokenAmount::new(12_500_000, 6));
+        assert_eq!(TokenAmount::from_ui_str(".000001", 6).unwrap(), TokenAmount::new(1, 6));
+        assert_eq!(TokenAmount::from_ui_str("1.500000000", 2).unwrap(), TokenAmount::new(150, 2));
+    }
+
+    #[test]
+    fn rejects_amounts_that_need_rounding() {
+        assert!(matches!(TokenAmount::from_ui_str("1.001", 2), Err(AppError::InvalidInput(_))));
+        assert!(matches!(TokenAmount::from_ui_str("0.5", 0), Err(AppError::InvalidInput(_))));
+        assert!(TokenAmount::from_decimal_with_scale(&decimal("1.23"), 1).is_err());
+    }
+
+    #[test]
+    fn rejects_malformed_amounts() {
+        for value in ["", ".", "-1", "1e3", "1.2.3", "12a"] {
+            assert!(TokenAmount::from_ui_str(value, 6).is_err(), "{:?} was accepted", value);
+        }
+    }
+
+    #[test]
+    fn handles_max_u64() {
+        let max = TokenAmount::new(u64::MAX, 9);
+        assert_eq!(max.to_ui_string(), "18446744073.709551615");
+        assert_eq!(TokenAmount::from_ui_str(&max.to_ui_string(), 9).unwrap(), max);
+        assert_eq!(TokenAmount::from_decimal(&max.to_decimal()).unwrap(), max);
+
+        assert!(TokenAmount::from_ui_str("18446744073.709551616", 9).is_err());
+        assert!(TokenAmount::from_decimal_with_scale(&decimal("18446744073.709551616"), 9).is_err());
+    }
+
+    #[test]
+    fn handles_zero_decimals() {
+        let amount = TokenAmount::from_ui_str("42", 0).unwrap();
+        assert_eq!(amount, TokenAmount::new(42, 0));
+        assert_eq!(amount.to_ui_string(), "42");
+        assert_eq!(amount.to_decimal(), decimal("42"));
+        assert_eq!(TokenAmount::from_decimal(&amount.to_decimal()).unwrap(), amount);
+    }
+
+    #[test]
+    fn rescales_decimals_without_losing_digits() {
+        assert_eq!(
+            TokenAmount::from_decimal_with_scale(&decimal("1.5"), 9).unwrap(),
+            TokenAmount::new(1_500_000_000, 9)
+        );
+        assert_eq!(
+            TokenAmount::from_decimal_with_scale(&decimal("7.000"), 0).unwrap(),
+            TokenAmount::new(7, 0)
+        );
+        assert!(TokenAmount::from_decimal_with_scale(&decimal("-1"), 0).is_err());
+    }
+
+    #[test]
+    fn decimal_scale_is_taken_as_decimals() {
+        let amount = TokenAmount::new(1_000, 3);
+        let stored = amount.to_decimal();
+        assert_eq!(stored.as_bigint_and_exponent().1, 3);
+        assert_eq!(TokenAmount::from_decimal(&stored).unwrap(), amount);
+    }
+
+    #[test]
+    fn serializes_raw_amount_as_string() {
+        let json = serde_json::to_value(TokenAmount::new(u64::MAX, 9)).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "amount": "18446744073709551615",
+                "decimals": 9,
+                "ui_amount": "18446744073.709551615"
+            })
+        );
+        assert_eq!(serde_json::from_value::<TokenAmount>(json).unwrap(), TokenAmount::new(u64::MAX, 9));
+    }
+}
@@ -0,0 +1,395 @@
+use crate::{
+    error::{AppError, Result},
+    AppState,
+};
+use anyhow::anyhow;
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Request, State},
+    http::{header::AUTHORIZATION, request::Parts},
+    middleware::Next,
+    response::Response,
+};
+use jsonwebtoken::{
+    decode, decode_header,
+    jwk::{JwkSet, KeyAlgorithm},
+    Algorithm, DecodingKey, Validation,
+};
+use serde::Deserialize;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+// Header carrying the caller's Supabase access token. `Authorization` is
+// already taken by the service API key sent from the Next.js bridge.
+pub const USER_TOKEN_HEADER: &str = "x-supabase-token";
+
+// Set of bearer keys accepted from the Next.js bridge. Several keys may be
+// active at once so a new key can be rolled out before the old one is removed.
+pub struct ApiKeys {
+    keys: Vec<String>,
+}
+
+impl ApiKeys {
+    pub fn from_env() -> Result<Self> {
+        let mut keys: Vec<String> = std::env::var("RUST_SERVICE_API_KEYS")
+            .unwrap_or_default()
+            .split(',')
+            .map(|key| key.trim().to_string())
+            .filter(|key| !key.is_empty())
+            .collect();
+
+        if let Ok(key) = std::env::var("RUST_SERVICE_API_KEY") {
+            let key = key.trim().to_string();
+            if !key.is_empty() && !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+
+        if keys.is_empty() {
+            return Err(anyhow!(
+                "RUST_SERVICE_API_KEY or RUST_SERVICE_API_KEYS environment variable must be set"
+            )
+            .into());
+        }
+
+        Ok(Self { keys })
+    }
+
+    pub fn is_valid(&self, candidate: &str) -> bool {
+        // Check every key so the time taken does not reveal which one matched
+        self.keys
+            .iter()
+            .fold(false, |found, key| constant_time_eq(key.as_bytes(), candidate.as_bytes()) | found)
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+// Rejects requests that do not carry `Authorization: Bearer <key>` with one of
+// the configured API keys
+pub async fn require_api_key(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Result<Response> {
+    let token = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim)
+        .ok_or(AppError::Unauthorized)?;
+
+    if !state.api_keys.is_valid(token) {
+        return Err(AppError::Unauthorized);
+    }
+
+    Ok(next.run(request).await)
+}
+
+#[derive(Debug, Deserialize)]
+struct SupabaseClaims {
+    sub: Uuid,
+}
+
+enum JwtKeys {
+    Secret(DecodingKey),
+    Jwks(JwkSet),
+}
+
+// Verifies Supabase-issued access tokens, either with the project's HS256
+// secret or with a JWKS document loaded from disk or over HTTP at startup
+pub struct JwtVerifier {
+    keys: JwtKeys,
+    audience: String,
+}
+
+impl JwtVerifier {
+    pub async fn from_env() -> Result<Self> {
+        let audience = std::env::var("SUPABASE_JWT_AUDIENCE")
+            .unwrap_or_else(|_| "authenticated".to_string());
+
+        let keys = if let Ok(secret) = std::env::var("SUPABASE_JWT_SECRET") {
+            JwtKeys::Secret(DecodingKey::from_secret(secret.as_bytes()))
+        } else if let Ok(path) = std::env::var("SUPABASE_JWKS_PATH") {
+            let contents = tokio::fs::read_to_string(&path)
+                .await
+                .map_err(|e| anyhow!("Failed to read JWKS file {}: {}", path, e))?;
+            let jwks: JwkSet = serde_json::from_str(&contents)
+                .map_err(|e| anyhow!("Invalid JWKS file {}: {}", path, e))?;
+            info!("Loaded {} JWT verification keys from {}", jwks.keys.len(), path);
+            JwtKeys::Jwks(jwks)
+        } else if let Ok(url) = std::env::var("SUPABASE_JWKS_URL") {
+            let jwks: JwkSet = reqwest::get(&url)
+                .await
+                .and_then(|response| response.error_for_status())
+                .map_err(|e| anyhow!("Failed to fetch JWKS from {}: {}", url, e))?
+                .json()
+                .await
+                .map_err(|e| anyhow!("Invalid JWKS from {}: {}", url, e))?;
+            info!("Loaded {} JWT verification keys from {}", jwks.keys.len(), url);
+            JwtKeys::Jwks(jwks)
+        } else {
+            return Err(anyhow!(
+                "One of SUPABASE_JWT_SECRET, SUPABASE_JWKS_PATH or SUPABASE_JWKS_URL must be set"
+            )
+            .into());
+        };
+
+        Ok(Self { keys, audience })
+    }
+
+    // Returns the user id (`sub` claim) of a valid token
+    pub fn verify(&self, token: &str) -> Result<Uuid> {
+        let header = decode_header(token).map_err(|_| AppError::Unauthorized)?;
+
+        let (key, algorithm) = match &self.keys {
+            JwtKeys::Secret(key) => (key.clone(), Algorithm::HS256),
+            JwtKeys::Jwks(jwks) => {
+                let kid = header.kid.as_deref().ok_or(AppError::Unauthorized)?;
+                let jwk = jwks.find(kid).ok_or(AppError::Unauthorized)?;
+                let key = DecodingKey::from_jwk(jwk).map_err(|_| AppError::Unauthorized)?;
+                // The key, not the token, decides which algorithm is acceptable
+                let algorithm = jwk
+                    .common
+                    .key_algorithm
+                    .as_ref()
+                    .and_then(signing_algorithm)
+                    .ok_or(AppError::Unauthorized)?;
+                (key, algorithm)
+            }
+        };
+
+        if header.alg != algorithm {
+            warn!("Rejected user token signed with {:?}, expected {:?}", header.alg, algorithm);
+            return Err(AppError::Unauthorized);
+        }
+
+        let mut validation = Validation::new(algorithm);
+        validation.set_audience(&[&self.audience]);
+
+        let data = decode::<SupabaseClaims>(token, &key, &validation).map_err(|e| {
+            warn!("Rejected user token: {}", e);
+            AppError::Unauthorized
+        })?;
+
+        Ok(data.claims.sub)
+    }
+}
+
+// Maps the `alg` published on a JWK to a signature algorithm; encryption-only
+// algorithms are never valid for verifying tokens
+fn signing_algorithm(algorithm: &KeyAlgorithm) -> Option<Algorithm> {
+    match algorithm {
+        KeyAlgorithm::HS256 => Some(Algorithm::HS256),
+        KeyAlgorithm::HS384 => Some(Algorithm::HS384),
+        KeyAlgorithm::HS512 => Some(Algorithm::HS512),
+        KeyAlgorithm::ES256 => Some(Algorithm::ES256),
+        KeyAlgorithm::ES384 => Some(Algorithm::ES384),
+        KeyAlgorithm::RS256 => Some(Algorithm::RS256),
+        KeyAlgorithm::RS384 => Some(Algorithm::RS384),
+        KeyAlgorithm::RS512 => Some(Algorithm::RS512),
+        KeyAlgorithm::PS256 => Some(Algorithm::PS256),
+        KeyAlgorithm::PS384 => Some(Algorithm::PS384),
+        KeyAlgorithm::PS512 => Some(Algorithm::PS512),
+        KeyAlgorithm::EdDSA => Some(Algorithm::EdDSA),
+        _ => None,
+    }
+}
+
+// Authenticated caller, resolved from a verified Supabase token and the role
+// stored on their `user_profiles` row
+#[derive(Debug, Clone)]
+pub struct AuthUser {
+    pub user_id: Uuid,
+    pub role: String,
+}
+
+impl AuthUser {
+    pub fn is_admin(&self) -> bool {
+        self.role == "admin"
+    }
+
+    // Only admins may act on behalf of another user
+    pub fn authorize(&self, user_id: Uuid) -> Result<()> {
+        if self.user_id == user_id || self.is_admin() {
+            Ok(())
+        } else {
+            Err(AppError::Forbidden(
+                "user_id does not match the authenticated user".to_string(),
+            ))
+        }
+    }
+}
+
+#[async_trait]
+impl FromRequestParts<AppState> for AuthUser {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self> {
+        let token = parts
+            .headers
+            .get(USER_TOKEN_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.strip_prefix("Bearer ").unwrap_or(value).trim())
+            .ok_or(AppError::Unauthorized)?;
+
+        let user_id = state.jwt.verify(token)?;
+
+        let role = state
+            .database
+            .get_user_role(user_id)
+            .await?
+            .ok_or(AppError::Unauthorized)?;
+
+        Ok(Self { user_id, role })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use serde::Serialize;
+
+    // 39 bytes, so its base64 form has neither padding nor URL-unsafe characters
+    const SECRET: &[u8] = b"test-signing-secret-0123456789abcdef-xy";
+    const SECRET_B64: &str = "dGVzdC1zaWduaW5nLXNlY3JldC0wMTIzNDU2Nzg5YWJjZGVmLXh5";
+
+    #[derive(Serialize)]
+    struct Claims {
+        sub: Uuid,
+        aud: &'static str,
+        exp: i64,
+    }
+
+    fn token(header: Header, secret: &[u8], sub: Uuid, expires_in: i64) -> String {
+        let claims = Claims {
+            sub,
+            aud: "authenticated",
+            exp: chrono::Utc::now().timestamp() + expires_in,
+        };
+        encode(&header, &claims, &EncodingKey::from_secret(secret)).unwrap()
+    }
+
+    fn hs256_token(secret: &[u8], sub: Uuid, expires_in: i64) -> String {
+        token(Header::new(Algorithm::HS256), secret, sub, expires_in)
+    }
+
+    fn secret_verifier() -> JwtVerifier {
+        JwtVerifier {
+            keys: JwtKeys::Secret(DecodingKey::from_secret(SECRET)),
+            audience: "authenticated".to_string(),
+        }
+    }
+
+    fn jwks_verifier(alg: &str) -> JwtVerifier {
+        let jwks: JwkSet = serde_json::from_value(serde_json::json!({
+            "keys": [{ "kty": "oct", "kid": "key-1", "alg": alg, "k": SECRET_B64 }]
+        }))
+        .unwrap();
+
+        JwtVerifier {
+            keys: JwtKeys::Jwks(jwks),
+            audience: "authenticated".to_string(),
+        }
+    }
+
+    fn jwks_header(algorithm: Algorithm) -> Header {
+        Header {
+            kid: Some("key-1".to_string()),
+            ..Header::new(algorithm)
+        }
+    }
+
+    fn user(role: &str) -> AuthUser {
+        AuthUser {
+            user_id: Uuid::new_v4(),
+            role: role.to_string(),
+        }
+    }
+
+    #[test]
+    fn accepts_valid_hs256_token() {
+        let sub = Uuid::new_v4();
+        let verified = secret_verifier().verify(&hs256_token(SECRET, sub, 3600)).unwrap();
+
+        assert_eq!(verified, sub);
+    }
+
+    #[test]
+    fn rejects_expired_token() {
+        let token = hs256_token(SECRET, Uuid::new_v4(), -3600);
+
+        assert!(matches!(secret_verifier().verify(&token), Err(AppError::Unauthorized)));
+    }
+
+    #[test]
+    fn rejects_token_signed_with_another_key() {
+        let token = hs256_token(b"some-other-secret", Uuid::new_v4(), 3600);
+
+        assert!(matches!(secret_verifier().verify(&token), Err(AppError::Unauthorized)));
+    }
+
+    #[test]
+    fn rejects_token_whose_alg_differs_from_the_secret() {
+        let token = token(Header::new(Algorithm::HS384), SECRET, Uuid::new_v4(), 3600);
+
+        assert!(matches!(secret_verifier().verify(&token), Err(AppError::Unauthorized)));
+    }
+
+    #[test]
+    fn jwks_key_algorithm_decides_what_is_accepted() {
+        let sub = Uuid::new_v4();
+        let verifier = jwks_verifier("HS256");
+
+        let matching = token(jwks_header(Algorithm::HS256), SECRET, sub, 3600);
+        assert_eq!(verifier.verify(&matching).unwrap(), sub);
+
+        // Same key material, but the token claims an algorithm the JWK does not
+        let mismatched = token(jwks_header(Algorithm::HS512), SECRET, sub, 3600);
+        assert!(matches!(verifier.verify(&mismatched), Err(AppError::Unauthorized)));
+    }
+
+    #[test]
+    fn jwks_rejects_unknown_kid() {
+        let header = Header {
+            kid: Some("key-2".to_string()),
+            ..Header::new(Algorithm::HS256)
+        };
+        let token = token(header, SECRET, Uuid::new_v4(), 3600);
+
+        assert!(matches!(jwks_verifier("HS256").verify(&token), Err(AppError::Unauthorized)));
+    }
+
+    #[test]
+    fn authorize_allows_owner_and_admin_only() {
+        let owner = user("user");
+        assert!(owner.authorize(owner.user_id).is_ok());
+
+        let admin = user("admin");
+        assert!(admin.authorize(owner.user_id).is_ok());
+
+        let other = user("user");
+        assert!(matches!(other.authorize(owner.user_id), Err(AppError::Forbidden(_))));
+    }
+
+    #[test]
+    fn api_keys_match_any_configured_key() {
+        let keys = ApiKeys {
+            keys: vec!["old-key".to_string(), "new-key".to_string()],
+        };
+
+        assert!(keys.is_valid("old-key"));
+        assert!(keys.is_valid("new-key"));
+        assert!(!keys.is_valid("new-ke"));
+        assert!(!keys.is_valid("other-key"));
+        assert!(!keys.is_valid(""));
+    }
+}
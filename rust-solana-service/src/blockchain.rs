@@ -1,27 +1,242 @@
-use crate::{error::Result, models::*};
+use crate::{
+    amount::TokenAmount,
+    error::{AppError, Result},
+    models::*,
+};
 use anyhow::anyhow;
-use solana_client::rpc_client::RpcClient;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use mpl_token_metadata::{
+    accounts::{MasterEdition, Metadata},
+    instructions::{
+        CreateMasterEditionV3, CreateMasterEditionV3InstructionArgs, CreateMetadataAccountV3,
+        CreateMetadataAccountV3InstructionArgs, UpdateMetadataAccountV2,
+        UpdateMetadataAccountV2InstructionArgs, VerifySizedCollectionItem,
+    },
+    types::{Collection, CollectionDetails, DataV2},
+    MAX_NAME_LENGTH, MAX_SYMBOL_LENGTH, MAX_URI_LENGTH,
+};
+use solana_client::{
+    client_error::ClientErrorKind,
+    nonblocking::rpc_client::RpcClient,
+    rpc_config::{RpcSendTransactionConfig, RpcSimulateTransactionConfig},
+    rpc_request::{RpcError, RpcResponseErrorData, TokenAccountsFilter},
+};
 use solana_sdk::{
+    account::Account,
     commitment_config::CommitmentConfig,
+    compute_budget::ComputeBudgetInstruction,
     instruction::Instruction,
+    message::Message,
+    packet::PACKET_DATA_SIZE,
+    program_pack::Pack,
     pubkey::Pubkey,
     signature::{Keypair, Signature, Signer},
-    system_instruction,
+    system_instruction::{self, SystemInstruction},
+    system_program,
     transaction::Transaction,
 };
 use spl_associated_token_account::{
-    get_associated_token_address, instruction::create_associated_token_account,
+    get_associated_token_address_with_program_id,
+    instruction::{create_associated_token_account, create_associated_token_account_idempotent},
 };
-use spl_token::{
-    instruction::{initialize_mint, mint_to, transfer},
-    state::Mint,
+// Token-2022 instruction builders accept either program id, so they serve
+// legacy SPL Token mints as well
+use spl_token_2022::{
+    extension::{non_transferable::NonTransferable, ExtensionType, StateWithExtensions},
+    instruction::{
+        burn_checked, freeze_account, initialize_mint, initialize_non_transferable_mint,
+        initialize_permanent_delegate, mint_to_checked, set_authority, thaw_account,
+        transfer_checked, AuthorityType,
+    },
+    state::{Account as TokenAccount, Mint},
 };
-use std::str::FromStr;
+use std::{str::FromStr, time::Duration};
 use tracing::{info, warn};
 
+// Most signatures that can be requested in one `getSignatureStatuses` call
+const SIGNATURE_STATUS_CHUNK: usize = 256;
+
+// Most accounts `getMultipleAccounts` accepts in one call
+const MULTIPLE_ACCOUNTS_CHUNK: usize = 100;
+
+// Most accounts `getRecentPrioritizationFees` accepts in one call
+const PRIORITIZATION_FEE_ACCOUNTS: usize = 128;
+
 pub struct BlockchainService {
     client: RpcClient,
     payer: Keypair,
+    // Optional ops wallet used only to top up the payer
+    treasury: Option<Keypair>,
+    retry_policy: RetryPolicy,
+    fee_defaults: PriorityFeeOptions,
+}
+
+// Token metadata requested on mint creation, if any
+fn requested_metadata(request: &CreateMintRequest) -> Result<Option<TokenMetadata>> {
+    let metadata = match (&request.name, &request.symbol, &request.uri) {
+        (None, None, None) => return Ok(None),
+        (Some(name), Some(symbol), uri) => TokenMetadata {
+            name: name.clone(),
+            symbol: symbol.clone(),
+            uri: uri.clone().unwrap_or_default(),
+        },
+        _ => {
+            return Err(AppError::InvalidInput(
+                "Token metadata needs both a name and a symbol".to_string(),
+            ))
+        }
+    };
+
+    validate_metadata(&metadata)?;
+
+    Ok(Some(metadata))
+}
+
+fn validate_metadata(metadata: &TokenMetadata) -> Result<()> {
+    let limits = [
+        ("name", &metadata.name, MAX_NAME_LENGTH),
+        ("symbol", &metadata.symbol, MAX_SYMBOL_LENGTH),
+        ("uri", &metadata.uri, MAX_URI_LENGTH),
+    ];
+
+    for (field, value, max) in limits {
+        if value.len() > max {
+            return Err(AppError::InvalidInput(format!(
+                "Metadata {} must be at most {} bytes",
+                field, max
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+// Mint state of either token program; Token-2022 mints may carry extensions
+// after the base layout
+fn unpack_mint(mint: &Pubkey, account: &Account) -> Result<(Mint, TokenProgram)> {
+    let program = TokenProgram::from_id(&account.owner)
+        .ok_or_else(|| AppError::InvalidInput(format!("{} is not a token mint", mint)))?;
+
+    Ok((StateWithExtensions::<Mint>::unpack(&account.data)?.base, program))
+}
+
+// Whether a transaction of `instructions` signed by `payer` alone stays
+// within the cluster's packet size
+fn fits_in_packet(instructions: &[Instruction], payer: &Pubkey) -> Result<bool> {
+    let transaction = Transaction::new_unsigned(Message::new(instructions, Some(payer)));
+    let size = bincode::serialized_size(&transaction)
+        .map_err(|e| anyhow!("Failed to size transaction: {}", e))?;
+
+    Ok(size <= PACKET_DATA_SIZE as u64)
+}
+
+// `None` while the transaction is unknown or only processed
+fn settled_outcome(signature: &Signature, status: Option<&TransactionStatus>) -> Option<BroadcastOutcome> {
+    let status = status?;
+
+    match status.status.as_str() {
+        "confirmed" => {
+            info!("Confirmed transaction {}", signature);
+            Some(BroadcastOutcome::Confirmed {
+                slot: status.slot.unwrap_or_default(),
+            })
+        }
+        "failed" => Some(BroadcastOutcome::Failed { slot: status.slot }),
+        _ => None,
+    }
+}
+
+// A master edition takes over the mint and freeze authorities of its mint
+pub fn master_edition_address(mint: &Pubkey) -> Pubkey {
+    MasterEdition::find_pda(mint).0
+}
+
+pub fn env_parse<T: FromStr>(name: &str) -> Option<T> {
+    std::env::var(name).ok().and_then(|value| value.parse().ok())
+}
+
+// How long to keep re-broadcasting a transaction and how many times it may be
+// rebuilt with a fresh blockhash once the original one has expired
+pub struct RetryPolicy {
+    pub rebroadcast_interval: Duration,
+    pub max_rebuilds: u32,
+}
+
+impl RetryPolicy {
+    pub fn from_env() -> Self {
+        Self {
+            rebroadcast_interval: Duration::from_millis(
+                env_parse("SOLANA_REBROADCAST_INTERVAL_MS").unwrap_or(2_000),
+            ),
+            max_rebuilds: env_parse("SOLANA_MAX_REBUILDS").unwrap_or(1),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum FreezeAction {
+    Freeze,
+    Thaw,
+}
+
+impl FreezeAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FreezeAction::Freeze => "freeze",
+            FreezeAction::Thaw => "thaw",
+        }
+    }
+}
+
+pub enum BroadcastOutcome {
+    Confirmed { slot: u64 },
+    Failed { slot: Option<u64> },
+    Expired,
+}
+
+// Compute-budget settings applied to a transaction. The price is in
+// micro-lamports per compute unit.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ComputeBudget {
+    pub compute_unit_limit: Option<u32>,
+    pub compute_unit_price: Option<u64>,
+}
+
+impl ComputeBudget {
+    fn instructions(&self) -> Vec<Instruction> {
+        let mut instructions = vec![];
+
+        if let Some(limit) = self.compute_unit_limit {
+            instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(limit));
+        }
+        if let Some(price) = self.compute_unit_price.filter(|price| *price > 0) {
+            instructions.push(ComputeBudgetInstruction::set_compute_unit_price(price));
+        }
+
+        instructions
+    }
+}
+
+// A signed (or partially signed) transaction together with the last block
+// height at which its blockhash is still accepted by the cluster
+pub struct BuiltTransaction {
+    pub transaction: Transaction,
+    pub last_valid_block_height: u64,
+    pub compute_budget: ComputeBudget,
+}
+
+impl BuiltTransaction {
+    pub fn new(transaction: Transaction, last_valid_block_height: u64, compute_budget: ComputeBudget) -> Self {
+        Self {
+            transaction,
+            last_valid_block_height,
+            compute_budget,
+        }
+    }
+
+    pub fn signature(&self) -> String {
+        self.transaction.signatures[0].to_string()
+    }
 }
 
 impl BlockchainService {
@@ -39,212 +254,1707 @@ impl BlockchainService {
 
         info!("Blockchain service initialized with payer: {}", payer.pubkey());
 
-        Ok(Self { client, payer })
+        let treasury = std::env::var("SOLANA_TREASURY_PRIVATE_KEY")
+            .ok()
+            .map(|key| Keypair::from_base58_string(&key));
+
+        let fee_defaults = PriorityFeeOptions {
+            compute_unit_limit: env_parse("SOLANA_COMPUTE_UNIT_LIMIT"),
+            compute_unit_price: env_parse("SOLANA_COMPUTE_UNIT_PRICE"),
+            priority_fee_percentile: env_parse("SOLANA_PRIORITY_FEE_PERCENTILE"),
+        };
+
+        Ok(Self {
+            client,
+            payer,
+            treasury,
+            retry_policy: RetryPolicy::from_env(),
+            fee_defaults,
+        })
+    }
+
+    // SOL balance plus the associated-token-account balance of each mint for
+    // every address, fetched with batched `getMultipleAccounts` calls. Missing
+    // wallets and ATAs count as zero; a missing mint is an error.
+    pub async fn get_balances(
+        &self,
+        addresses: &[Pubkey],
+        mints: &[Pubkey],
+    ) -> Result<Vec<(u64, Vec<TokenBalance>)>> {
+        let wallets = self.get_multiple_accounts(addresses).await?;
+
+        let mut mint_states = Vec::with_capacity(mints.len());
+        for (mint, account) in mints.iter().zip(self.get_multiple_accounts(mints).await?) {
+            let account = account.ok_or_else(|| AppError::NotFound(format!("Mint {} not found", mint)))?;
+            mint_states.push(unpack_mint(mint, &account)?);
+        }
+
+        let atas: Vec<Pubkey> = addresses
+            .iter()
+            .flat_map(|address| {
+                mints.iter().zip(&mint_states).map(move |(mint, (_, program))| {
+                    get_associated_token_address_with_program_id(address, mint, &program.id())
+                })
+            })
+            .collect();
+        let mut ata_accounts = self.get_multiple_accounts(&atas).await?.into_iter();
+        let mut ata_addresses = atas.iter();
+
+        let mut balances = Vec::with_capacity(addresses.len());
+        for wallet in wallets {
+            let lamports = wallet.map(|account| account.lamports).unwrap_or(0);
+
+            let mut tokens = Vec::with_capacity(mints.len());
+            for (mint, (mint_state, _)) in mints.iter().zip(&mint_states) {
+                let ata = ata_addresses.next().copied().unwrap_or_default();
+                let amount = ata_accounts
+                    .next()
+                    .flatten()
+                    .and_then(|account| StateWithExtensions::<TokenAccount>::unpack(&account.data).ok())
+                    .map(|account| account.base.amount)
+                    .unwrap_or(0);
+
+                tokens.push(TokenBalance {
+                    mint: mint.to_string(),
+                    token_account: ata.to_string(),
+                    balance: TokenAmount::new(amount, mint_state.decimals),
+                });
+            }
+
+            balances.push((lamports, tokens));
+        }
+
+        Ok(balances)
     }
 
-    pub async fn get_balance(&self, pubkey: &Pubkey) -> Result<u64> {
-        let balance = self.client.get_balance(pubkey)?;
-        Ok(balance)
+    async fn get_multiple_accounts(&self, pubkeys: &[Pubkey]) -> Result<Vec<Option<Account>>> {
+        let mut accounts = Vec::with_capacity(pubkeys.len());
+
+        for chunk in pubkeys.chunks(MULTIPLE_ACCOUNTS_CHUNK) {
+            accounts.extend(self.client.get_multiple_accounts(chunk).await?);
+        }
+
+        Ok(accounts)
     }
 
-    pub async fn create_mint(
+    async fn create_mint_instructions(
         &self,
-        decimals: u8,
-        mint_authority: &str,
-        freeze_authority: Option<&str>,
-    ) -> Result<MintResponse> {
-        let mint_keypair = Keypair::new();
-        let mint_pubkey = mint_keypair.pubkey();
+        mint_pubkey: &Pubkey,
+        request: &CreateMintRequest,
+    ) -> Result<Vec<Instruction>> {
+        let CreateMintRequest { decimals, token_program, extensions, .. } = request;
 
-        let mint_authority_pubkey = Pubkey::from_str(mint_authority)
+        let mint_authority_pubkey = Pubkey::from_str(&request.mint_authority)
             .map_err(|_| anyhow!("Invalid mint authority address"))?;
 
-        let freeze_authority_pubkey = if let Some(freeze_auth) = freeze_authority {
+        let freeze_authority_pubkey = if let Some(freeze_auth) = &request.freeze_authority {
             Some(Pubkey::from_str(freeze_auth)
                 .map_err(|_| anyhow!("Invalid freeze authority address"))?)
         } else {
             None
         };
 
+        let permanent_delegate_pubkey = if let Some(delegate) = &extensions.permanent_delegate {
+            Some(Pubkey::from_str(delegate)
+                .map_err(|_| AppError::InvalidInput("Invalid permanent delegate address".to_string()))?)
+        } else {
+            None
+        };
+
+        if !extensions.is_empty() && *token_program != TokenProgram::Token2022 {
+            return Err(AppError::InvalidInput(
+                "Mint extensions require the token_2022 program".to_string(),
+            ));
+        }
+
+        let program_id = token_program.id();
+
+        // Extensions are initialized before the mint itself, and the account
+        // must be sized for them up front
+        let mut extension_types = vec![];
+        let mut extension_instructions = vec![];
+        if extensions.non_transferable {
+            extension_types.push(ExtensionType::NonTransferable);
+            extension_instructions.push(initialize_non_transferable_mint(&program_id, mint_pubkey)?);
+        }
+        if let Some(delegate) = &permanent_delegate_pubkey {
+            extension_types.push(ExtensionType::PermanentDelegate);
+            extension_instructions.push(initialize_permanent_delegate(&program_id, mint_pubkey, delegate)?);
+        }
+
+        let mint_len = if extension_types.is_empty() {
+            Mint::LEN
+        } else {
+            ExtensionType::try_calculate_account_len::<Mint>(&extension_types)?
+        };
+
         // Calculate rent exemption amount for mint account
-        let mint_rent = self.client.get_minimum_balance_for_rent_exemption(Mint::LEN)?;
+        let mint_rent = self.client.get_minimum_balance_for_rent_exemption(mint_len).await?;
 
         let mut instructions = vec![
             // Create mint account
             system_instruction::create_account(
                 &self.payer.pubkey(),
-                &mint_pubkey,
+                mint_pubkey,
                 mint_rent,
-                Mint::LEN as u64,
-                &spl_token::id(),
+                mint_len as u64,
+                &program_id,
             ),
-            // Initialize mint
-            initialize_mint(
-                &spl_token::id(),
-                &mint_pubkey,
-                &mint_authority_pubkey,
-                freeze_authority_pubkey.as_ref(),
-                decimals,
-            )?,
         ];
+        instructions.extend(extension_instructions);
+        // Initialize mint
+        instructions.push(initialize_mint(
+            &program_id,
+            mint_pubkey,
+            &mint_authority_pubkey,
+            freeze_authority_pubkey.as_ref(),
+            *decimals,
+        )?);
 
-        let recent_blockhash = self.client.get_latest_blockhash()?;
-        let transaction = Transaction::new_signed_with_payer(
-            &instructions,
-            Some(&self.payer.pubkey()),
-            &[&self.payer, &mint_keypair],
-            recent_blockhash,
-        );
+        // The metadata program requires the mint authority's signature, and
+        // the service keeps the update authority so metadata can be edited
+        if let Some(metadata) = requested_metadata(request)? {
+            self.ensure_payer_signs(&mint_authority_pubkey, "mint authority")?;
 
-        let signature = self.client.send_and_confirm_transaction(&transaction)?;
-        
-        info!("Created mint {} with signature {}", mint_pubkey, signature);
+            instructions.push(self.create_metadata_instruction(mint_pubkey, &metadata));
+        }
 
-        Ok(MintResponse {
-            mint_address: mint_pubkey.to_string(),
-            signature: signature.to_string(),
-            decimals,
-            mint_authority: mint_authority.to_string(),
-            freeze_authority: freeze_authority.map(|s| s.to_string()),
+        Ok(instructions)
+    }
+
+    // Metadata for a mint whose authority is the service payer, which also
+    // becomes the update authority
+    fn create_metadata_instruction(&self, mint: &Pubkey, metadata: &TokenMetadata) -> Instruction {
+        self.create_nft_metadata_instruction(mint, metadata, None, None)
+    }
+
+    fn create_nft_metadata_instruction(
+        &self,
+        mint: &Pubkey,
+        metadata: &TokenMetadata,
+        collection: Option<&Pubkey>,
+        collection_details: Option<CollectionDetails>,
+    ) -> Instruction {
+        CreateMetadataAccountV3 {
+            metadata: Metadata::find_pda(mint).0,
+            mint: *mint,
+            mint_authority: self.payer.pubkey(),
+            payer: self.payer.pubkey(),
+            update_authority: (self.payer.pubkey(), true),
+            system_program: system_program::id(),
+            rent: None,
+        }
+        .instruction(CreateMetadataAccountV3InstructionArgs {
+            data: DataV2 {
+                name: metadata.name.clone(),
+                symbol: metadata.symbol.clone(),
+                uri: metadata.uri.clone(),
+                seller_fee_basis_points: 0,
+                creators: None,
+                // Collection membership starts unverified until the
+                // collection authority signs a verify instruction
+                collection: collection.map(|key| Collection { verified: false, key: *key }),
+                uses: None,
+            },
+            is_mutable: true,
+            collection_details,
         })
     }
 
-    pub async fn mint_tokens(
+    // Instructions for a one-of-one NFT held by `owner`: a 0-decimal mint,
+    // one token in the owner's ATA, metadata, and a master edition with no
+    // prints, which takes over the mint and freeze authorities
+    fn nft_instructions(
+        &self,
+        mint: &Pubkey,
+        mint_rent: u64,
+        owner: &Pubkey,
+        metadata: &TokenMetadata,
+        collection: Option<&Pubkey>,
+        collection_details: Option<CollectionDetails>,
+    ) -> Result<Vec<Instruction>> {
+        let payer = self.payer.pubkey();
+        let program_id = TokenProgram::SplToken.id();
+        let owner_ata = get_associated_token_address_with_program_id(owner, mint, &program_id);
+
+        Ok(vec![
+            system_instruction::create_account(&payer, mint, mint_rent, Mint::LEN as u64, &program_id),
+            initialize_mint(&program_id, mint, &payer, Some(&payer), 0)?,
+            create_associated_token_account(&payer, owner, mint, &program_id),
+            mint_to_checked(&program_id, mint, &owner_ata, &payer, &[], 1, 0)?,
+            self.create_nft_metadata_instruction(mint, metadata, collection, collection_details),
+            CreateMasterEditionV3 {
+                edition: master_edition_address(mint),
+                mint: *mint,
+                update_authority: payer,
+                mint_authority: payer,
+                payer,
+                metadata: Metadata::find_pda(mint).0,
+                token_program: program_id,
+                system_program: system_program::id(),
+                rent: None,
+            }
+            .instruction(CreateMasterEditionV3InstructionArgs { max_supply: Some(0) }),
+        ])
+    }
+
+    fn verify_collection_instruction(&self, collection: &Pubkey, item: &Pubkey) -> Instruction {
+        VerifySizedCollectionItem {
+            metadata: Metadata::find_pda(item).0,
+            collection_authority: self.payer.pubkey(),
+            payer: self.payer.pubkey(),
+            collection_mint: *collection,
+            collection: Metadata::find_pda(collection).0,
+            collection_master_edition_account: master_edition_address(collection),
+            collection_authority_record: None,
+        }
+        .instruction()
+    }
+
+    // Builds a collection NFT held by the service payer, which stays its
+    // update authority and so can verify items into it
+    pub async fn build_collection(
+        &self,
+        metadata: &TokenMetadata,
+        fees: &PriorityFeeOptions,
+    ) -> Result<(BuiltTransaction, Pubkey)> {
+        validate_metadata(metadata)?;
+
+        let mint_keypair = Keypair::new();
+        let mint = mint_keypair.pubkey();
+        let mint_rent = self.client.get_minimum_balance_for_rent_exemption(Mint::LEN).await?;
+
+        let instructions = self.nft_instructions(
+            &mint,
+            mint_rent,
+            &self.payer.pubkey(),
+            metadata,
+            None,
+            Some(CollectionDetails::V1 { size: 0 }),
+        )?;
+
+        let built = self.signed_with(&[&self.payer, &mint_keypair], instructions, fees).await?;
+
+        info!("Built collection {} with signature {}", mint, built.signature());
+
+        Ok((built, mint))
+    }
+
+    // Builds an NFT in `collection` for `owner`, verified into the
+    // collection in the same transaction
+    pub async fn build_collection_item(
+        &self,
+        collection: &Pubkey,
+        owner: &Pubkey,
+        metadata: &TokenMetadata,
+        fees: &PriorityFeeOptions,
+    ) -> Result<(BuiltTransaction, Pubkey)> {
+        validate_metadata(metadata)?;
+
+        let mint_keypair = Keypair::new();
+        let mint = mint_keypair.pubkey();
+        let mint_rent = self.client.get_minimum_balance_for_rent_exemption(Mint::LEN).await?;
+
+        let mut instructions =
+            self.nft_instructions(&mint, mint_rent, owner, metadata, Some(collection), None)?;
+        instructions.push(self.verify_collection_instruction(collection, &mint));
+
+        let built = self.signed_with(&[&self.payer, &mint_keypair], instructions, fees).await?;
+
+        info!("Built item {} of collection {} for {} with signature {}", mint, collection, owner, built.signature());
+
+        Ok((built, mint))
+    }
+
+    // Builds the verification of an item that already names `collection` in
+    // its metadata, signed by the service payer as collection authority.
+    // Returns the item's current metadata alongside.
+    pub async fn verify_collection_item(
+        &self,
+        collection: &Pubkey,
+        item: &Pubkey,
+        fees: &PriorityFeeOptions,
+    ) -> Result<(BuiltTransaction, TokenMetadata)> {
+        let (_, metadata) = self.get_metadata_account(item).await?;
+
+        match &metadata.collection {
+            Some(membership) if membership.key == *collection && membership.verified => {
+                return Err(AppError::Conflict("Item is already verified in this collection".to_string()))
+            }
+            Some(membership) if membership.key == *collection => {}
+            _ => {
+                return Err(AppError::InvalidInput(
+                    "Item metadata does not name this collection".to_string(),
+                ))
+            }
+        }
+
+        let built = self
+            .payer_signed(vec![self.verify_collection_instruction(collection, item)], fees)
+            .await?;
+
+        info!("Built verification of {} in collection {} with signature {}", item, collection, built.signature());
+
+        let current_value = |value: &str| value.trim_end_matches('\0').to_string();
+
+        Ok((
+            built,
+            TokenMetadata {
+                name: current_value(&metadata.name),
+                symbol: current_value(&metadata.symbol),
+                uri: current_value(&metadata.uri),
+            },
+        ))
+    }
+
+    // Whether `item` names `collection` in its metadata, and whether the
+    // collection authority has verified that
+    pub async fn get_collection_membership(&self, collection: &Pubkey, item: &Pubkey) -> Result<(bool, bool)> {
+        let (_, metadata) = self.get_metadata_account(item).await?;
+
+        Ok(match metadata.collection {
+            Some(membership) if membership.key == *collection => (true, membership.verified),
+            _ => (false, false),
+        })
+    }
+
+    // Builds a one-of-one ticket NFT for `owner` in a single transaction:
+    // a 0-decimal mint with metadata, one token in the owner's ATA, and the
+    // mint authority revoked so no second copy can exist. The service keeps
+    // the freeze authority so the ticket can be redeemed at check-in.
+    pub async fn build_ticket(
+        &self,
+        owner: &Pubkey,
+        metadata: &TokenMetadata,
+        fees: &PriorityFeeOptions,
+    ) -> Result<(BuiltTransaction, Pubkey)> {
+        validate_metadata(metadata)?;
+
+        let mint_keypair = Keypair::new();
+        let mint = mint_keypair.pubkey();
+        let payer = self.payer.pubkey();
+        let program_id = TokenProgram::SplToken.id();
+        let owner_ata = get_associated_token_address_with_program_id(owner, &mint, &program_id);
+
+        let mint_rent = self.client.get_minimum_balance_for_rent_exemption(Mint::LEN).await?;
+
+        let instructions = vec![
+            system_instruction::create_account(&payer, &mint, mint_rent, Mint::LEN as u64, &program_id),
+            initialize_mint(&program_id, &mint, &payer, Some(&payer), 0)?,
+            self.create_metadata_instruction(&mint, metadata),
+            create_associated_token_account(&payer, owner, &mint, &program_id),
+            mint_to_checked(&program_id, &mint, &owner_ata, &payer, &[], 1, 0)?,
+            set_authority(&program_id, &mint, None, AuthorityType::MintTokens, &payer, &[])?,
+        ];
+
+        let built = self.signed_with(&[&self.payer, &mint_keypair], instructions, fees).await?;
+
+        info!("Built ticket {} for {} with signature {}", mint, owner, built.signature());
+
+        Ok((built, mint))
+    }
+
+    // Builds the transaction creating a new mint, signed by the service
+    // payer and the fresh mint keypair, along with the mint's stored row
+    pub async fn build_mint(&self, request: &CreateMintRequest) -> Result<(BuiltTransaction, MintResponse)> {
+        let mint_keypair = Keypair::new();
+        let mint_pubkey = mint_keypair.pubkey();
+
+        let instructions = self.create_mint_instructions(&mint_pubkey, request).await?;
+        let built = self
+            .signed_with(&[&self.payer, &mint_keypair], instructions, &request.fees)
+            .await?;
+
+        info!("Built creation of mint {} with signature {}", mint_pubkey, built.signature());
+
+        let mint = MintResponse {
+            mint_address: mint_pubkey.to_string(),
+            signature: built.signature(),
+            decimals: request.decimals,
+            mint_authority: Some(request.mint_authority.clone()),
+            freeze_authority: request.freeze_authority.clone(),
+            token_program: request.token_program,
+            extensions: request.extensions.clone(),
+            metadata: requested_metadata(request)?,
+        };
+
+        Ok((built, mint))
+    }
+
+    async fn mint_instructions(
         &self,
         mint: &Pubkey,
         destination: &Pubkey,
-        amount: u64,
-        authority: &str,
-    ) -> Result<TransactionResponse> {
-        let authority_pubkey = Pubkey::from_str(authority)
-            .map_err(|_| anyhow!("Invalid authority address"))?;
+        amount: TokenAmount,
+        authority: &Pubkey,
+    ) -> Result<Vec<Instruction>> {
+        let program_id = self.token_program_for(mint).await?.id();
 
         // Get or create associated token account
-        let destination_ata = get_associated_token_address(destination, mint);
+        let destination_ata = get_associated_token_address_with_program_id(destination, mint, &program_id);
         
         let mut instructions = vec![];
 
         // Check if ATA exists
-        if self.client.get_account(&destination_ata).is_err() {
+        if self.client.get_account(&destination_ata).await.is_err() {
             instructions.push(create_associated_token_account(
                 &self.payer.pubkey(),
                 destination,
                 mint,
-                &spl_token::id(),
+                &program_id,
             ));
         }
 
-        // Add mint instruction
-        instructions.push(mint_to(
-            &spl_token::id(),
+        // Add mint instruction; the checked variant makes the token program
+        // reject the amount if `decimals` does not match the mint
+        instructions.push(mint_to_checked(
+            &program_id,
             mint,
             &destination_ata,
-            &authority_pubkey,
+            authority,
             &[],
-            amount,
+            amount.raw(),
+            amount.decimals(),
         )?);
 
-        let recent_blockhash = self.client.get_latest_blockhash()?;
-        let transaction = Transaction::new_signed_with_payer(
-            &instructions,
-            Some(&self.payer.pubkey()),
-            &[&self.payer], // Authority should sign separately
-            recent_blockhash,
-        );
+        Ok(instructions)
+    }
+
+    // Builds a mint transaction fully signed by the service payer. Nothing is
+    // broadcast until `send_transaction` is called, so the signature can be
+    // recorded first.
+    pub async fn mint_tokens(
+        &self,
+        mint: &Pubkey,
+        destination: &Pubkey,
+        amount: TokenAmount,
+        authority: &str,
+        fees: &PriorityFeeOptions,
+    ) -> Result<BuiltTransaction> {
+        let authority_pubkey = Pubkey::from_str(authority)
+            .map_err(|_| anyhow!("Invalid authority address"))?;
 
-        let signature = self.client.send_and_confirm_transaction(&transaction)?;
-        let slot = self.client.get_slot()?;
+        self.ensure_payer_signs(&authority_pubkey, "authority")?;
 
-        info!("Minted {} tokens to {} with signature {}", amount, destination_ata, signature);
+        let instructions = self
+            .mint_instructions(mint, destination, amount, &authority_pubkey)
+            .await?;
 
-        Ok(TransactionResponse {
-            signature: signature.to_string(),
-            slot: Some(slot),
-            status: "confirmed".to_string(),
-        })
+        let built = self.payer_signed(instructions, fees).await?;
+
+        info!("Built mint of {} tokens to {} with signature {}", amount, destination, built.signature());
+
+        Ok(built)
     }
 
-    pub async fn transfer_tokens(
+    // Builds one transaction minting to as many of `recipients`, in order, as
+    // fit in a packet, creating their ATAs where missing. The service payer
+    // must be the mint authority. Returns how many recipients were packed,
+    // which is always at least one.
+    pub async fn build_batch_mint(
+        &self,
+        mint: &Pubkey,
+        recipients: &[(Pubkey, TokenAmount)],
+        fees: &PriorityFeeOptions,
+    ) -> Result<(BuiltTransaction, usize)> {
+        if recipients.is_empty() {
+            return Err(AppError::InvalidInput("No recipients to mint to".to_string()));
+        }
+
+        let payer = self.payer.pubkey();
+        let program_id = self.token_program_for(mint).await?.id();
+
+        // Leave room for both compute-budget instructions
+        let budget = ComputeBudget {
+            compute_unit_limit: Some(0),
+            compute_unit_price: Some(1),
+        }
+        .instructions();
+
+        let mut instructions = vec![];
+        let mut packed = 0;
+
+        for (destination, amount) in recipients {
+            let destination_ata = get_associated_token_address_with_program_id(destination, mint, &program_id);
+            let recipient_instructions = [
+                // Idempotent so a recipient listed twice, or whose ATA appears
+                // meanwhile, does not fail the whole batch
+                create_associated_token_account_idempotent(&payer, destination, mint, &program_id),
+                mint_to_checked(
+                    &program_id,
+                    mint,
+                    &destination_ata,
+                    &payer,
+                    &[],
+                    amount.raw(),
+                    amount.decimals(),
+                )?,
+            ];
+
+            let candidate: Vec<Instruction> = budget
+                .iter()
+                .chain(&instructions)
+                .chain(&recipient_instructions)
+                .cloned()
+                .collect();
+            if packed > 0 && !fits_in_packet(&candidate, &payer)? {
+                break;
+            }
+
+            instructions.extend(recipient_instructions);
+            packed += 1;
+        }
+
+        let built = self.payer_signed(instructions, fees).await?;
+
+        info!("Built batch mint of {} to {} recipients with signature {}", mint, packed, built.signature());
+
+        Ok((built, packed))
+    }
+
+    // Builds a mint transaction signed only by the fee payer, to be completed
+    // by the wallet holding the mint authority
+    pub async fn prepare_mint_tokens(
+        &self,
+        mint: &Pubkey,
+        destination: &Pubkey,
+        amount: TokenAmount,
+        authority: &str,
+        fees: &PriorityFeeOptions,
+    ) -> Result<BuiltTransaction> {
+        let authority_pubkey = Pubkey::from_str(authority)
+            .map_err(|_| anyhow!("Invalid authority address"))?;
+
+        let instructions = self
+            .mint_instructions(mint, destination, amount, &authority_pubkey)
+            .await?;
+
+        self.partially_signed(instructions, fees).await
+    }
+
+    async fn transfer_instructions(
         &self,
         mint: &Pubkey,
         from: &Pubkey,
         to: &Pubkey,
-        amount: u64,
-        owner: &str,
-    ) -> Result<TransactionResponse> {
-        let owner_pubkey = Pubkey::from_str(owner)
-            .map_err(|_| anyhow!("Invalid owner address"))?;
-
-        let from_ata = get_associated_token_address(from, mint);
-        let to_ata = get_associated_token_address(to, mint);
+        amount: TokenAmount,
+        owner: &Pubkey,
+    ) -> Result<Vec<Instruction>> {
+        let program_id = self.token_program_for(mint).await?.id();
+        let from_ata = get_associated_token_address_with_program_id(from, mint, &program_id);
+        let to_ata = get_associated_token_address_with_program_id(to, mint, &program_id);
 
         let mut instructions = vec![];
 
         // Check if destination ATA exists
-        if self.client.get_account(&to_ata).is_err() {
+        if self.client.get_account(&to_ata).await.is_err() {
             instructions.push(create_associated_token_account(
                 &self.payer.pubkey(),
                 to,
                 mint,
-                &spl_token::id(),
+                &program_id,
             ));
         }
 
         // Add transfer instruction
-        instructions.push(transfer(
-            &spl_token::id(),
+        instructions.push(transfer_checked(
+            &program_id,
             &from_ata,
+            mint,
             &to_ata,
-            &owner_pubkey,
+            owner,
             &[],
-            amount,
+            amount.raw(),
+            amount.decimals(),
         )?);
 
-        let recent_blockhash = self.client.get_latest_blockhash()?;
-        let transaction = Transaction::new_signed_with_payer(
-            &instructions,
-            Some(&self.payer.pubkey()),
-            &[&self.payer], // Owner should sign separately
-            recent_blockhash,
-        );
+        Ok(instructions)
+    }
 
-        let signature = self.client.send_and_confirm_transaction(&transaction)?;
-        let slot = self.client.get_slot()?;
+    // Builds a transfer transaction fully signed by the service payer, to be
+    // broadcast with `send_transaction`
+    pub async fn transfer_tokens(
+        &self,
+        mint: &Pubkey,
+        from: &Pubkey,
+        to: &Pubkey,
+        amount: TokenAmount,
+        owner: &str,
+        fees: &PriorityFeeOptions,
+    ) -> Result<BuiltTransaction> {
+        let owner_pubkey = Pubkey::from_str(owner)
+            .map_err(|_| anyhow!("Invalid owner address"))?;
 
-        info!("Transferred {} tokens from {} to {} with signature {}", amount, from_ata, to_ata, signature);
+        self.ensure_payer_signs(&owner_pubkey, "owner")?;
 
-        Ok(TransactionResponse {
-            signature: signature.to_string(),
-            slot: Some(slot),
-            status: "confirmed".to_string(),
-        })
-    }
+        let instructions = self
+            .transfer_instructions(mint, from, to, amount, &owner_pubkey)
+            .await?;
 
-    pub async fn get_transaction_status(&self, signature: &str) -> Result<TransactionStatus> {
-        let signature = Signature::from_str(signature)
-            .map_err(|_| anyhow!("Invalid signature format"))?;
+        let built = self.payer_signed(instructions, fees).await?;
 
-        match self.client.get_signature_status(&signature)? {
-            Some(status) => {
-                let status_str = if status.is_ok() {
-                    "confirmed"
-                } else {
-                    "failed"
-                };
+        info!("Built transfer of {} tokens from {} to {} with signature {}", amount, from, to, built.signature());
+
+        Ok(built)
+    }
+
+    // Builds a transfer transaction signed only by the fee payer, to be
+    // completed by the wallet that owns the source account
+    pub async fn prepare_transfer_tokens(
+        &self,
+        mint: &Pubkey,
+        from: &Pubkey,
+        to: &Pubkey,
+        amount: TokenAmount,
+        owner: &str,
+        fees: &PriorityFeeOptions,
+    ) -> Result<BuiltTransaction> {
+        let owner_pubkey = Pubkey::from_str(owner)
+            .map_err(|_| anyhow!("Invalid owner address"))?;
+
+        let instructions = self
+            .transfer_instructions(mint, from, to, amount, &owner_pubkey)
+            .await?;
+
+        self.partially_signed(instructions, fees).await
+    }
+
+    pub async fn get_mint(&self, mint: &Pubkey) -> Result<Mint> {
+        Ok(self.get_mint_account(mint).await?.0)
+    }
+
+    // Mint state together with the token program that owns the mint account
+    pub async fn get_mint_account(&self, mint: &Pubkey) -> Result<(Mint, TokenProgram)> {
+        let account = self
+            .client
+            .get_account(mint)
+            .await
+            .map_err(|_| AppError::NotFound(format!("Mint {} not found", mint)))?;
+
+        unpack_mint(mint, &account)
+    }
+
+    pub async fn token_program_for(&self, mint: &Pubkey) -> Result<TokenProgram> {
+        Ok(self.get_mint_account(mint).await?.1)
+    }
+
+    // Whether the mint carries the Token-2022 `NonTransferable` extension
+    pub async fn is_non_transferable(&self, mint: &Pubkey) -> Result<bool> {
+        let account = self
+            .client
+            .get_account(mint)
+            .await
+            .map_err(|_| AppError::NotFound(format!("Mint {} not found", mint)))?;
+
+        if TokenProgram::from_id(&account.owner) != Some(TokenProgram::Token2022) {
+            return Ok(false);
+        }
+
+        let state = StateWithExtensions::<Mint>::unpack(&account.data)?;
+
+        Ok(state.get_extension::<NonTransferable>().is_ok())
+    }
+
+    async fn get_metadata_account(&self, mint: &Pubkey) -> Result<(Pubkey, Metadata)> {
+        let metadata_pubkey = Metadata::find_pda(mint).0;
+        let account = self
+            .client
+            .get_account(&metadata_pubkey)
+            .await
+            .map_err(|_| AppError::NotFound(format!("Mint {} has no token metadata", mint)))?;
+
+        let metadata = Metadata::from_bytes(&account.data)
+            .map_err(|e| anyhow!("Invalid metadata account {}: {}", metadata_pubkey, e))?;
+
+        Ok((metadata_pubkey, metadata))
+    }
+
+    // Builds an update of a mint's name, symbol or URI, fully signed by the
+    // service payer as the metadata update authority. Returns the new values.
+    pub async fn update_token_metadata(
+        &self,
+        mint: &Pubkey,
+        request: &UpdateMetadataRequest,
+    ) -> Result<(BuiltTransaction, TokenMetadata)> {
+        let (metadata_pubkey, current) = self.get_metadata_account(mint).await?;
+
+        self.ensure_payer_signs(&current.update_authority, "metadata update authority")?;
+        if !current.is_mutable {
+            return Err(AppError::InvalidInput("Token metadata is immutable".to_string()));
+        }
+
+        // Older metadata accounts pad their strings with NULs
+        let current_value = |value: &str| value.trim_end_matches('\0').to_string();
+        let metadata = TokenMetadata {
+            name: request.name.clone().unwrap_or_else(|| current_value(&current.name)),
+            symbol: request.symbol.clone().unwrap_or_else(|| current_value(&current.symbol)),
+            uri: request.uri.clone().unwrap_or_else(|| current_value(&current.uri)),
+        };
+        validate_metadata(&metadata)?;
 
-                Ok(TransactionStatus {
-                    signature: signature.to_string(),
-                    status: status_str.to_string(),
-                    confirmations: None, // Could be enhanced to get actual confirmations
-                    slot: None, // Could be enhanced to get slot
+        let instruction = UpdateMetadataAccountV2 {
+            metadata: metadata_pubkey,
+            update_authority: current.update_authority,
+        }
+        .instruction(UpdateMetadataAccountV2InstructionArgs {
+            data: Some(DataV2 {
+                name: metadata.name.clone(),
+                symbol: metadata.symbol.clone(),
+                uri: metadata.uri.clone(),
+                seller_fee_basis_points: current.seller_fee_basis_points,
+                creators: current.creators,
+                collection: current.collection,
+                uses: current.uses,
+            }),
+            new_update_authority: None,
+            primary_sale_happened: None,
+            is_mutable: None,
+        });
+
+        let built = self.payer_signed(vec![instruction], &request.fees).await?;
+
+        info!("Built metadata update for mint {} with signature {}", mint, built.signature());
+
+        Ok((built, metadata))
+    }
+
+    // Every SPL Token and Token-2022 account held by `owner`, from
+    // `getTokenAccountsByOwner`
+    pub async fn get_token_accounts(&self, owner: &Pubkey) -> Result<Vec<TokenAccountInfo>> {
+        let mut accounts = Vec::new();
+        for program in [TokenProgram::SplToken, TokenProgram::Token2022] {
+            accounts.extend(
+                self.client
+                    .get_token_accounts_by_owner(owner, TokenAccountsFilter::ProgramId(program.id()))
+                    .await?,
+            );
+        }
+
+        let token_accounts = accounts
+            .into_iter()
+            .filter_map(|keyed| {
+                // jsonParsed data: { "parsed": { "info": { ... } } }
+                let data = serde_json::to_value(&keyed.account.data).ok()?;
+                let info = &data["parsed"]["info"];
+                let token_amount = &info["tokenAmount"];
+
+                Some(TokenAccountInfo {
+                    address: keyed.pubkey,
+                    mint: info["mint"].as_str()?.to_string(),
+                    owner: info["owner"].as_str()?.to_string(),
+                    balance: TokenAmount::new(
+                        token_amount["amount"].as_str()?.parse().ok()?,
+                        token_amount["decimals"].as_u64()? as u8,
+                    ),
                 })
+            })
+            .collect();
+
+        Ok(token_accounts)
+    }
+
+    async fn burn_instructions(
+        &self,
+        mint: &Pubkey,
+        owner: &Pubkey,
+        amount: TokenAmount,
+        authority: &Pubkey,
+    ) -> Result<Vec<Instruction>> {
+        let program_id = self.token_program_for(mint).await?.id();
+        let owner_ata = get_associated_token_address_with_program_id(owner, mint, &program_id);
+
+        Ok(vec![burn_checked(
+            &program_id,
+            &owner_ata,
+            mint,
+            authority,
+            &[],
+            amount.raw(),
+            amount.decimals(),
+        )?])
+    }
+
+    // Builds a burn transaction fully signed by the service payer, for tokens
+    // held in accounts the service itself controls
+    pub async fn burn_tokens(
+        &self,
+        mint: &Pubkey,
+        owner: &Pubkey,
+        amount: TokenAmount,
+        authority: &str,
+        fees: &PriorityFeeOptions,
+    ) -> Result<BuiltTransaction> {
+        let authority_pubkey = Pubkey::from_str(authority)
+            .map_err(|_| anyhow!("Invalid authority address"))?;
+
+        self.ensure_payer_signs(&authority_pubkey, "authority")?;
+
+        let instructions = self
+            .burn_instructions(mint, owner, amount, &authority_pubkey)
+            .await?;
+
+        let built = self.payer_signed(instructions, fees).await?;
+
+        info!("Built burn of {} tokens from {} with signature {}", amount, owner, built.signature());
+
+        Ok(built)
+    }
+
+    // Builds a burn transaction signed only by the fee payer, to be completed
+    // by the token account owner or its delegate
+    pub async fn prepare_burn_tokens(
+        &self,
+        mint: &Pubkey,
+        owner: &Pubkey,
+        amount: TokenAmount,
+        authority: &str,
+        fees: &PriorityFeeOptions,
+    ) -> Result<BuiltTransaction> {
+        let authority_pubkey = Pubkey::from_str(authority)
+            .map_err(|_| anyhow!("Invalid authority address"))?;
+
+        let instructions = self
+            .burn_instructions(mint, owner, amount, &authority_pubkey)
+            .await?;
+
+        self.partially_signed(instructions, fees).await
+    }
+
+    async fn freeze_instructions(
+        &self,
+        action: FreezeAction,
+        mint: &Pubkey,
+        owner: &Pubkey,
+        freeze_authority: &Pubkey,
+    ) -> Result<Vec<Instruction>> {
+        let program_id = self.token_program_for(mint).await?.id();
+        let owner_ata = get_associated_token_address_with_program_id(owner, mint, &program_id);
+
+        let instruction = match action {
+            FreezeAction::Freeze => {
+                freeze_account(&program_id, &owner_ata, mint, freeze_authority, &[])?
             }
-            None => Ok(TransactionStatus {
+            FreezeAction::Thaw => {
+                thaw_account(&program_id, &owner_ata, mint, freeze_authority, &[])?
+            }
+        };
+
+        Ok(vec![instruction])
+    }
+
+    // Builds a freeze or thaw of an owner's token account, fully signed by the
+    // service payer acting as the mint's freeze authority
+    pub async fn set_account_frozen(
+        &self,
+        action: FreezeAction,
+        mint: &Pubkey,
+        owner: &Pubkey,
+        freeze_authority: &str,
+        fees: &PriorityFeeOptions,
+    ) -> Result<BuiltTransaction> {
+        let freeze_authority_pubkey = Pubkey::from_str(freeze_authority)
+            .map_err(|_| anyhow!("Invalid freeze authority address"))?;
+
+        self.ensure_payer_signs(&freeze_authority_pubkey, "freeze authority")?;
+
+        let instructions = self
+            .freeze_instructions(action, mint, owner, &freeze_authority_pubkey)
+            .await?;
+        let built = self.payer_signed(instructions, fees).await?;
+
+        info!("Built {} of {} for mint {} with signature {}", action.as_str(), owner, mint, built.signature());
+
+        Ok(built)
+    }
+
+    // Builds a freeze or thaw signed only by the fee payer, to be completed by
+    // the wallet holding the freeze authority
+    pub async fn prepare_set_account_frozen(
+        &self,
+        action: FreezeAction,
+        mint: &Pubkey,
+        owner: &Pubkey,
+        freeze_authority: &str,
+        fees: &PriorityFeeOptions,
+    ) -> Result<BuiltTransaction> {
+        let freeze_authority_pubkey = Pubkey::from_str(freeze_authority)
+            .map_err(|_| anyhow!("Invalid freeze authority address"))?;
+
+        let instructions = self
+            .freeze_instructions(action, mint, owner, &freeze_authority_pubkey)
+            .await?;
+
+        self.partially_signed(instructions, fees).await
+    }
+
+    async fn set_authority_instructions(
+        &self,
+        mint: &Pubkey,
+        kind: AuthorityKind,
+        current_authority: &Pubkey,
+        new_authority: Option<&Pubkey>,
+    ) -> Result<Vec<Instruction>> {
+        let authority_type = match kind {
+            AuthorityKind::Mint => AuthorityType::MintTokens,
+            AuthorityKind::Freeze => AuthorityType::FreezeAccount,
+        };
+
+        let program_id = self.token_program_for(mint).await?.id();
+
+        Ok(vec![set_authority(
+            &program_id,
+            mint,
+            new_authority,
+            authority_type,
+            current_authority,
+            &[],
+        )?])
+    }
+
+    // Builds a change of a mint's mint or freeze authority, fully signed by
+    // the service payer as the current authority. `None` revokes it.
+    pub async fn set_mint_authority(
+        &self,
+        mint: &Pubkey,
+        kind: AuthorityKind,
+        current_authority: &str,
+        new_authority: Option<&Pubkey>,
+        fees: &PriorityFeeOptions,
+    ) -> Result<BuiltTransaction> {
+        let current_authority_pubkey = Pubkey::from_str(current_authority)
+            .map_err(|_| anyhow!("Invalid current authority address"))?;
+
+        self.ensure_payer_signs(&current_authority_pubkey, "current authority")?;
+
+        let instructions =
+            self.set_authority_instructions(mint, kind, &current_authority_pubkey, new_authority).await?;
+        let built = self.payer_signed(instructions, fees).await?;
+
+        info!("Built {} authority change for mint {} with signature {}", kind.as_str(), mint, built.signature());
+
+        Ok(built)
+    }
+
+    // Builds an authority change signed only by the fee payer, to be
+    // completed by the wallet holding the current authority
+    pub async fn prepare_set_mint_authority(
+        &self,
+        mint: &Pubkey,
+        kind: AuthorityKind,
+        current_authority: &str,
+        new_authority: Option<&Pubkey>,
+        fees: &PriorityFeeOptions,
+    ) -> Result<BuiltTransaction> {
+        let current_authority_pubkey = Pubkey::from_str(current_authority)
+            .map_err(|_| anyhow!("Invalid current authority address"))?;
+
+        let instructions =
+            self.set_authority_instructions(mint, kind, &current_authority_pubkey, new_authority).await?;
+
+        self.partially_signed(instructions, fees).await
+    }
+
+    // Builds a SOL transfer out of the service payer
+    pub async fn transfer_sol(
+        &self,
+        from: &Pubkey,
+        to: &Pubkey,
+        lamports: u64,
+        fees: &PriorityFeeOptions,
+    ) -> Result<BuiltTransaction> {
+        self.ensure_payer_signs(from, "sender")?;
+
+        let instructions = vec![system_instruction::transfer(from, to, lamports)];
+        let built = self.payer_signed(instructions, fees).await?;
+
+        info!("Built transfer of {} lamports from {} to {} with signature {}", lamports, from, to, built.signature());
+
+        Ok(built)
+    }
+
+    // Builds a SOL transfer signed only by the fee payer, to be completed by
+    // the sender's wallet
+    pub async fn prepare_transfer_sol(
+        &self,
+        from: &Pubkey,
+        to: &Pubkey,
+        lamports: u64,
+        fees: &PriorityFeeOptions,
+    ) -> Result<BuiltTransaction> {
+        let instructions = vec![system_instruction::transfer(from, to, lamports)];
+
+        self.partially_signed(instructions, fees).await
+    }
+
+    // Moves SOL from the treasury into the fee payer. The treasury pays the
+    // fee itself so this works even when the payer is empty.
+    pub async fn fund_payer(
+        &self,
+        lamports: u64,
+        fees: &PriorityFeeOptions,
+    ) -> Result<BuiltTransaction> {
+        let treasury = self.treasury.as_ref().ok_or_else(|| {
+            AppError::InvalidInput("SOLANA_TREASURY_PRIVATE_KEY is not configured".to_string())
+        })?;
+
+        let instructions = vec![system_instruction::transfer(
+            &treasury.pubkey(),
+            &self.payer.pubkey(),
+            lamports,
+        )];
+        let built = self.signed_by(treasury, instructions, fees).await?;
+
+        info!("Built payer top-up of {} lamports with signature {}", lamports, built.signature());
+
+        Ok(built)
+    }
+
+    pub fn payer_pubkey(&self) -> Pubkey {
+        self.payer.pubkey()
+    }
+
+    pub fn treasury_pubkey(&self) -> Option<Pubkey> {
+        self.treasury.as_ref().map(|treasury| treasury.pubkey())
+    }
+
+    pub async fn simulate_create_mint(&self, request: &CreateMintRequest) -> Result<SimulationResponse> {
+        // Signatures are not verified, so a throwaway address stands in for
+        // the mint keypair
+        let mint_pubkey = Keypair::new().pubkey();
+        let instructions = self.create_mint_instructions(&mint_pubkey, request).await?;
+
+        self.simulate(instructions, &request.fees).await
+    }
+
+    pub async fn simulate_mint_tokens(
+        &self,
+        mint: &Pubkey,
+        destination: &Pubkey,
+        amount: TokenAmount,
+        authority: &str,
+        fees: &PriorityFeeOptions,
+    ) -> Result<SimulationResponse> {
+        let authority_pubkey = Pubkey::from_str(authority)
+            .map_err(|_| anyhow!("Invalid authority address"))?;
+
+        let instructions = self
+            .mint_instructions(mint, destination, amount, &authority_pubkey)
+            .await?;
+
+        self.simulate(instructions, fees).await
+    }
+
+    pub async fn simulate_transfer_tokens(
+        &self,
+        mint: &Pubkey,
+        from: &Pubkey,
+        to: &Pubkey,
+        amount: TokenAmount,
+        owner: &str,
+        fees: &PriorityFeeOptions,
+    ) -> Result<SimulationResponse> {
+        let owner_pubkey = Pubkey::from_str(owner)
+            .map_err(|_| anyhow!("Invalid owner address"))?;
+
+        let instructions = self
+            .transfer_instructions(mint, from, to, amount, &owner_pubkey)
+            .await?;
+
+        self.simulate(instructions, fees).await
+    }
+
+    // Runs the instructions through `simulateTransaction` without signing or
+    // broadcasting, and works out the fee and the rent the payer would fund
+    async fn simulate(
+        &self,
+        mut instructions: Vec<Instruction>,
+        fees: &PriorityFeeOptions,
+    ) -> Result<SimulationResponse> {
+        let compute_budget = self.resolve_compute_budget(&instructions, fees).await?;
+        instructions.splice(0..0, compute_budget.instructions());
+
+        // The token program is the sixth account of an ATA creation
+        let token_account_programs: Vec<Pubkey> = instructions
+            .iter()
+            .filter(|instruction| instruction.program_id == spl_associated_token_account::id())
+            .filter_map(|instruction| instruction.accounts.get(5).map(|meta| meta.pubkey))
+            .collect();
+        let creates_token_account = !token_account_programs.is_empty();
+
+        // Lamports moved into new accounts by `create_account`, plus the
+        // associated token accounts the ATA program funds from the payer
+        let mut rent_lamports: u64 = instructions
+            .iter()
+            .filter(|instruction| instruction.program_id == system_program::id())
+            .filter_map(|instruction| bincode::deserialize(&instruction.data).ok())
+            .map(|instruction| match instruction {
+                SystemInstruction::CreateAccount { lamports, .. } => lamports,
+                _ => 0,
+            })
+            .sum();
+        for program_id in token_account_programs {
+            // Token-2022 ATAs always carry the immutable-owner extension
+            let len = if program_id == spl_token_2022::id() {
+                ExtensionType::try_calculate_account_len::<TokenAccount>(&[ExtensionType::ImmutableOwner])?
+            } else {
+                TokenAccount::LEN
+            };
+
+            rent_lamports += self.client.get_minimum_balance_for_rent_exemption(len).await?;
+        }
+
+        let recent_blockhash = self.client.get_latest_blockhash().await?;
+        let message = Message::new_with_blockhash(
+            &instructions,
+            Some(&self.payer.pubkey()),
+            &recent_blockhash,
+        );
+        let fee_lamports = self.client.get_fee_for_message(&message).await?;
+
+        let config = RpcSimulateTransactionConfig {
+            sig_verify: false,
+            replace_recent_blockhash: true,
+            commitment: Some(self.client.commitment()),
+            ..RpcSimulateTransactionConfig::default()
+        };
+        let result = self
+            .client
+            .simulate_transaction_with_config(&Transaction::new_unsigned(message), config)
+            .await?
+            .value;
+
+        Ok(SimulationResponse {
+            success: result.err.is_none(),
+            error: result.err.map(|err| err.to_string()),
+            logs: result.logs.unwrap_or_default(),
+            units_consumed: result.units_consumed,
+            fee_lamports,
+            creates_token_account,
+            rent_lamports,
+            compute_unit_limit: compute_budget.compute_unit_limit,
+            compute_unit_price: compute_budget.compute_unit_price,
+        })
+    }
+
+    // Checks that a transaction returned by a wallet carries every required
+    // signature and that all of them are valid
+    pub fn verify_signed_transaction(&self, transaction: &Transaction) -> Result<()> {
+        if !transaction.is_signed() {
+            return Err(AppError::InvalidInput(
+                "Transaction is missing required signatures".to_string(),
+            ));
+        }
+
+        transaction
+            .verify()
+            .map_err(|_| AppError::InvalidInput("Transaction signature verification failed".to_string()))?;
+
+        Ok(())
+    }
+
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry_policy
+    }
+
+    // Sends the transaction and keeps re-broadcasting the same signed bytes
+    // until it is confirmed, fails on-chain or its blockhash expires. The first
+    // send runs preflight so malformed transactions are rejected up front,
+    // as `TransactionFailed` since they never reach the cluster.
+    // `Expired` is only returned once the ledger history has no trace of the
+    // transaction, so it is safe to rebuild or give up on.
+    pub async fn broadcast_until_expired(&self, built: &BuiltTransaction) -> Result<BroadcastOutcome> {
+        let transaction = &built.transaction;
+        let signature = transaction.signatures[0];
+
+        if let Err(err) = self.client.send_transaction(transaction).await {
+            if let ClientErrorKind::RpcError(RpcError::RpcResponseError {
+                data: RpcResponseErrorData::SendTransactionPreflightFailure(_),
+                ..
+            }) = err.kind()
+            {
+                return Err(AppError::TransactionFailed(format!(
+                    "Transaction {} was rejected in preflight: {}",
+                    signature, err
+                )));
+            }
+            return Err(err.into());
+        }
+
+        let resend_config = RpcSendTransactionConfig {
+            skip_preflight: true,
+            ..RpcSendTransactionConfig::default()
+        };
+
+        loop {
+            tokio::time::sleep(self.retry_policy.rebroadcast_interval).await;
+
+            let status = self.get_signature_statuses(&[signature]).await?.pop().flatten();
+            if let Some(outcome) = settled_outcome(&signature, status.as_ref()) {
+                return Ok(outcome);
+            }
+
+            if self.client.get_block_height().await? > built.last_valid_block_height {
+                // Processed but not yet confirmed: it either reaches our
+                // commitment or is dropped with its fork, so keep polling
+                if status.is_some() {
+                    continue;
+                }
+
+                // It may still have landed in the last few blocks
+                let status = self
+                    .get_signature_statuses_with_history(&[signature])
+                    .await?
+                    .pop()
+                    .flatten();
+                match settled_outcome(&signature, status.as_ref()) {
+                    Some(outcome) => return Ok(outcome),
+                    None if status.is_some() => continue,
+                    None => return Ok(BroadcastOutcome::Expired),
+                }
+            }
+
+            if let Err(err) = self
+                .client
+                .send_transaction_with_config(transaction, resend_config)
+                .await
+            {
+                warn!("Re-broadcast of {} failed: {}", signature, err);
+            }
+        }
+    }
+
+    // Re-signs the same instructions against a fresh blockhash. Only possible
+    // when the service payer is the sole signer.
+    pub async fn rebuild(&self, built: &BuiltTransaction) -> Result<Option<BuiltTransaction>> {
+        let message = &built.transaction.message;
+        if message.header.num_required_signatures != 1 || message.account_keys[0] != self.payer.pubkey() {
+            return Ok(None);
+        }
+
+        let (recent_blockhash, last_valid_block_height) = self
+            .client
+            .get_latest_blockhash_with_commitment(self.client.commitment())
+            .await?;
+
+        let mut transaction = Transaction::new_unsigned(message.clone());
+        transaction
+            .try_sign(&[&self.payer], recent_blockhash)
+            .map_err(|e| anyhow!("Failed to sign transaction: {}", e))?;
+
+        Ok(Some(BuiltTransaction::new(transaction, last_valid_block_height, built.compute_budget)))
+    }
+
+    // Signers other than the fee payer that still have to sign
+    pub fn pending_signers(&self, transaction: &Transaction) -> Vec<String> {
+        let required = transaction.message.header.num_required_signatures as usize;
+
+        transaction
+            .message
+            .account_keys
+            .iter()
+            .take(required)
+            .filter(|key| **key != self.payer.pubkey())
+            .map(|key| key.to_string())
+            .collect()
+    }
+
+    pub fn encode_transaction(transaction: &Transaction) -> Result<String> {
+        let bytes = bincode::serialize(transaction)
+            .map_err(|e| anyhow!("Failed to serialize transaction: {}", e))?;
+        Ok(BASE64.encode(bytes))
+    }
+
+    pub fn decode_transaction(encoded: &str) -> Result<Transaction> {
+        let bytes = BASE64
+            .decode(encoded)
+            .map_err(|_| AppError::InvalidInput("Transaction is not valid base64".to_string()))?;
+        bincode::deserialize(&bytes)
+            .map_err(|_| AppError::InvalidInput("Transaction could not be decoded".to_string()))
+    }
+
+    async fn payer_signed(
+        &self,
+        instructions: Vec<Instruction>,
+        fees: &PriorityFeeOptions,
+    ) -> Result<BuiltTransaction> {
+        self.signed_by(&self.payer, instructions, fees).await
+    }
+
+    // Signs with `signer` as both fee payer and the only signer
+    async fn signed_by(
+        &self,
+        signer: &Keypair,
+        instructions: Vec<Instruction>,
+        fees: &PriorityFeeOptions,
+    ) -> Result<BuiltTransaction> {
+        self.signed_with(&[signer], instructions, fees).await
+    }
+
+    // Fully signs with `signers`, the first of which pays the fee. Only
+    // transactions signed by the payer alone can be rebuilt later.
+    async fn signed_with(
+        &self,
+        signers: &[&Keypair],
+        mut instructions: Vec<Instruction>,
+        fees: &PriorityFeeOptions,
+    ) -> Result<BuiltTransaction> {
+        let compute_budget = self.resolve_compute_budget(&instructions, fees).await?;
+        instructions.splice(0..0, compute_budget.instructions());
+
+        let (recent_blockhash, last_valid_block_height) = self
+            .client
+            .get_latest_blockhash_with_commitment(self.client.commitment())
+            .await?;
+
+        let transaction = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&signers[0].pubkey()),
+            signers,
+            recent_blockhash,
+        );
+
+        Ok(BuiltTransaction::new(transaction, last_valid_block_height, compute_budget))
+    }
+
+    async fn partially_signed(
+        &self,
+        mut instructions: Vec<Instruction>,
+        fees: &PriorityFeeOptions,
+    ) -> Result<BuiltTransaction> {
+        let compute_budget = self.resolve_compute_budget(&instructions, fees).await?;
+        instructions.splice(0..0, compute_budget.instructions());
+
+        let (recent_blockhash, last_valid_block_height) = self
+            .client
+            .get_latest_blockhash_with_commitment(self.client.commitment())
+            .await?;
+
+        let mut transaction = Transaction::new_with_payer(&instructions, Some(&self.payer.pubkey()));
+        transaction
+            .try_partial_sign(&[&self.payer], recent_blockhash)
+            .map_err(|e| anyhow!("Failed to sign transaction: {}", e))?;
+
+        Ok(BuiltTransaction::new(transaction, last_valid_block_height, compute_budget))
+    }
+
+    // Request options win over the service defaults. An explicit price wins
+    // over a percentile; a percentile is resolved against recent
+    // prioritization fees paid for the accounts the transaction writes to.
+    async fn resolve_compute_budget(
+        &self,
+        instructions: &[Instruction],
+        fees: &PriorityFeeOptions,
+    ) -> Result<ComputeBudget> {
+        let compute_unit_limit = fees.compute_unit_limit.or(self.fee_defaults.compute_unit_limit);
+
+        let compute_unit_price = match (fees.compute_unit_price, fees.priority_fee_percentile) {
+            (Some(price), _) => Some(price),
+            (None, Some(percentile)) => Some(self.priority_fee_percentile(instructions, percentile).await?),
+            (None, None) => match (
+                self.fee_defaults.compute_unit_price,
+                self.fee_defaults.priority_fee_percentile,
+            ) {
+                (Some(price), _) => Some(price),
+                (None, Some(percentile)) => {
+                    Some(self.priority_fee_percentile(instructions, percentile).await?)
+                }
+                (None, None) => None,
+            },
+        };
+
+        Ok(ComputeBudget {
+            compute_unit_limit,
+            compute_unit_price,
+        })
+    }
+
+    async fn priority_fee_percentile(
+        &self,
+        instructions: &[Instruction],
+        percentile: u8,
+    ) -> Result<u64> {
+        if percentile > 100 {
+            return Err(AppError::InvalidInput(
+                "priority_fee_percentile must be between 0 and 100".to_string(),
+            ));
+        }
+
+        let mut accounts: Vec<Pubkey> = instructions
+            .iter()
+            .flat_map(|instruction| instruction.accounts.iter())
+            .filter(|meta| meta.is_writable)
+            .map(|meta| meta.pubkey)
+            .collect();
+        accounts.sort();
+        accounts.dedup();
+        accounts.truncate(PRIORITIZATION_FEE_ACCOUNTS);
+
+        let mut fees: Vec<u64> = self
+            .client
+            .get_recent_prioritization_fees(&accounts)
+            .await?
+            .into_iter()
+            .map(|fee| fee.prioritization_fee)
+            .collect();
+
+        if fees.is_empty() {
+            return Ok(0);
+        }
+
+        fees.sort_unstable();
+        let index = (fees.len() - 1) * percentile as usize / 100;
+
+        Ok(fees[index])
+    }
+
+    // The direct mint/transfer paths can only sign with the service payer, so
+    // any other authority has to go through the prepare/submit flow
+    fn ensure_payer_signs(&self, signer: &Pubkey, role: &str) -> Result<()> {
+        if *signer != self.payer.pubkey() {
+            return Err(AppError::InvalidInput(format!(
+                "The {} must sign this transaction; use the prepare endpoint and submit the wallet-signed transaction",
+                role
+            )));
+        }
+        Ok(())
+    }
+
+    pub async fn get_transaction_status(&self, signature: &str) -> Result<TransactionStatus> {
+        let signature = Signature::from_str(signature)
+            .map_err(|_| anyhow!("Invalid signature format"))?;
+
+        let status = self
+            .get_signature_statuses(&[signature])
+            .await?
+            .pop()
+            .flatten()
+            .unwrap_or(TransactionStatus {
                 signature: signature.to_string(),
                 status: "not_found".to_string(),
                 confirmations: None,
                 slot: None,
-            }),
+            });
+
+        Ok(status)
+    }
+
+    // Looks up many signatures at once, chunked to the RPC limit. Entries are
+    // `None` for signatures the cluster has not seen; seen-but-unconfirmed
+    // transactions are reported as "processed". Only the recent status cache
+    // is searched, so a transaction older than that also comes back `None`.
+    pub async fn get_signature_statuses(
+        &self,
+        signatures: &[Signature],
+    ) -> Result<Vec<Option<TransactionStatus>>> {
+        self.signature_statuses(signatures, false).await
+    }
+
+    // Like `get_signature_statuses`, but also searches the ledger history.
+    // Slower, so it is kept for deciding that a transaction never landed.
+    pub async fn get_signature_statuses_with_history(
+        &self,
+        signatures: &[Signature],
+    ) -> Result<Vec<Option<TransactionStatus>>> {
+        self.signature_statuses(signatures, true).await
+    }
+
+    async fn signature_statuses(
+        &self,
+        signatures: &[Signature],
+        search_history: bool,
+    ) -> Result<Vec<Option<TransactionStatus>>> {
+        let mut results = Vec::with_capacity(signatures.len());
+
+        for chunk in signatures.chunks(SIGNATURE_STATUS_CHUNK) {
+            let statuses = if search_history {
+                self.client.get_signature_statuses_with_history(chunk).await?.value
+            } else {
+                self.client.get_signature_statuses(chunk).await?.value
+            };
+
+            for (signature, status) in chunk.iter().zip(statuses) {
+                results.push(status.map(|status| {
+                    let status_str = if status.err.is_some() {
+                        "failed"
+                    } else if status.satisfies_commitment(self.client.commitment()) {
+                        "confirmed"
+                    } else {
+                        "processed"
+                    };
+
+                    TransactionStatus {
+                        signature: signature.to_string(),
+                        status: status_str.to_string(),
+                        confirmations: status.confirmations.map(|c| c as u64),
+                        slot: Some(status.slot),
+                    }
+                }));
+            }
         }
+
+        Ok(results)
+    }
+
+    pub async fn get_block_height(&self) -> Result<u64> {
+        Ok(self.client.get_block_height().await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{routing::post, Json, Router};
+    use solana_sdk::{hash::Hash, program_option::COption};
+    use std::{sync::Arc, time::Instant};
+    use tokio::task::JoinSet;
+
+    const RPC_DELAY: Duration = Duration::from_millis(100);
+    const CONCURRENT_REQUESTS: usize = 20;
+    // getAccountInfo for the mint and the ATA, getLatestBlockhash,
+    // sendTransaction and getSignatureStatuses
+    const ROUND_TRIPS_PER_REQUEST: u32 = 5;
+
+    // Just enough of a cluster on a local port for a mint or transfer to be
+    // built, sent and confirmed. Every call is answered after `RPC_DELAY`.
+    async fn mock_rpc(mint: Pubkey, mint_authority: Pubkey) -> String {
+        let mut data = vec![0; Mint::LEN];
+        Mint::pack(
+            Mint {
+                mint_authority: COption::Some(mint_authority),
+                supply: 0,
+                decimals: 6,
+                is_initialized: true,
+                freeze_authority: COption::None,
+            },
+            &mut data,
+        )
+        .unwrap();
+        let mint_account = serde_json::json!({
+            "lamports": 1_461_600,
+            "data": [BASE64.encode(&data), "base64"],
+            "owner": spl_token::id().to_string(),
+            "executable": false,
+            "rentEpoch": 0,
+            "space": Mint::LEN
+        });
+
+        let handle = move |Json(request): Json<serde_json::Value>| {
+            let mint_account = mint_account.clone();
+            async move {
+                tokio::time::sleep(RPC_DELAY).await;
+
+                let params = &request["params"];
+                let result = match request["method"].as_str().unwrap_or_default() {
+                    "getVersion" => serde_json::json!({ "solana-core": "1.18.0", "feature-set": 0 }),
+                    // Only the mint exists, so every ATA gets created
+                    "getAccountInfo" => {
+                        let value = if params[0] == mint.to_string() {
+                            mint_account
+                        } else {
+                            serde_json::Value::Null
+                        };
+                        serde_json::json!({ "context": { "slot": 1 }, "value": value })
+                    }
+                    "getLatestBlockhash" => serde_json::json!({
+                        "context": { "slot": 1 },
+                        "value": { "blockhash": Hash::new_unique().to_string(), "lastValidBlockHeight": 1_000 }
+                    }),
+                    "sendTransaction" => {
+                        let wire = BASE64.decode(params[0].as_str().unwrap()).unwrap();
+                        let transaction: Transaction = bincode::deserialize(&wire).unwrap();
+                        serde_json::json!(transaction.signatures[0].to_string())
+                    }
+                    "getSignatureStatuses" => {
+                        let statuses: Vec<_> = params[0]
+                            .as_array()
+                            .unwrap()
+                            .iter()
+                            .map(|_| {
+                                serde_json::json!({
+                                    "slot": 2,
+                                    "confirmations": null,
+                                    "err": null,
+                                    "status": { "Ok": null },
+                                    "confirmationStatus": "finalized"
+                                })
+                            })
+                            .collect();
+                        serde_json::json!({ "context": { "slot": 2 }, "value": statuses })
+                    }
+                    "getBlockHeight" => serde_json::json!(1),
+                    method => panic!("Unexpected RPC call {}", method),
+                };
+
+                Json(serde_json::json!({ "jsonrpc": "2.0", "id": request["id"], "result": result }))
+            }
+        };
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            axum::serve(listener, Router::new().route("/", post(handle))).await.unwrap();
+        });
+
+        url
+    }
+
+    fn service(rpc_url: String, payer: Keypair) -> BlockchainService {
+        BlockchainService {
+            client: RpcClient::new_with_commitment(rpc_url, CommitmentConfig::confirmed()),
+            payer,
+            treasury: None,
+            retry_policy: RetryPolicy {
+                rebroadcast_interval: Duration::from_millis(10),
+                max_rebuilds: 0,
+            },
+            fee_defaults: PriorityFeeOptions::default(),
+        }
+    }
+
+    // The mock node and every request share this single-threaded runtime, so
+    // the requests can only overlap if none of them blocks the thread
+    #[tokio::test]
+    async fn concurrent_mints_and_transfers_proceed_in_parallel() {
+        let payer = Keypair::new();
+        let payer_pubkey = payer.pubkey();
+        let mint = Pubkey::new_unique();
+        let blockchain = Arc::new(service(mock_rpc(mint, payer_pubkey).await, payer));
+        let fees = PriorityFeeOptions::default();
+        let amount = TokenAmount::new(1_000_000, 6);
+
+        let started = Instant::now();
+        let mut requests = JoinSet::new();
+        for i in 0..CONCURRENT_REQUESTS {
+            let blockchain = blockchain.clone();
+            let fees = fees.clone();
+            let authority = payer_pubkey.to_string();
+
+            requests.spawn(async move {
+                let recipient = Pubkey::new_unique();
+                let built = if i % 2 == 0 {
+                    blockchain.mint_tokens(&mint, &recipient, amount, &authority, &fees).await?
+                } else {
+                    blockchain
+                        .transfer_tokens(&mint, &payer_pubkey, &recipient, amount, &authority, &fees)
+                        .await?
+                };
+                blockchain.broadcast_until_expired(&built).await
+            });
+        }
+        while let Some(outcome) = requests.join_next().await {
+            assert!(matches!(outcome.unwrap().unwrap(), BroadcastOutcome::Confirmed { .. }));
+        }
+        let elapsed = started.elapsed();
+
+        // One request takes about ROUND_TRIPS_PER_REQUEST delays; run one
+        // after another they would take CONCURRENT_REQUESTS times that
+        let one_request = RPC_DELAY * ROUND_TRIPS_PER_REQUEST;
+        assert!(
+            elapsed < one_request * 3,
+            "{} requests of about {:?} each took {:?}",
+            CONCURRENT_REQUESTS,
+            one_request,
+            elapsed
+        );
     }
 }
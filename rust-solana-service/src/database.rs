@@ -1,5 +1,6 @@
-use crate::{error::Result, models::*};
+use crate::{amount::TokenAmount, error::Result, models::*, settlement};
 use sqlx::{PgPool, Row};
+use std::time::Duration;
 use uuid::Uuid;
 
 pub struct DatabaseService {
@@ -24,9 +25,9 @@ impl DatabaseService {
             r#"
             INSERT INTO blockchain_transactions (
                 id, user_id, transaction_hash, transaction_type, amount,
-                token_address, from_address, to_address, status, block_number, metadata,
-                created_at, updated_at
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, NOW(), NOW())
+                token_address, from_address, to_address, status, block_number,
+                gas_used, gas_price, metadata, created_at, updated_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, NOW(), NOW())
             ON CONFLICT (transaction_hash) DO UPDATE SET
                 status = EXCLUDED.status,
                 block_number = EXCLUDED.block_number,
@@ -36,12 +37,14 @@ impl DatabaseService {
             transaction.user_id,
             transaction.transaction_hash,
             transaction.transaction_type,
-            transaction.amount,
+            transaction.amount.map(|amount| amount.to_decimal()),
             transaction.token_address,
             transaction.from_address,
             transaction.to_address,
             transaction.status,
             transaction.block_number.map(|n| n as i64),
+            transaction.gas_used,
+            transaction.gas_price,
             transaction.metadata
         )
         .execute(&self.pool)
@@ -54,15 +57,23 @@ impl DatabaseService {
         sqlx::query!(
             r#"
             INSERT INTO token_mints (
-                id, mint_address, decimals, mint_authority, freeze_authority, created_at
-            ) VALUES ($1, $2, $3, $4, $5, NOW())
+                id, mint_address, decimals, mint_authority, freeze_authority, metadata, created_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, NOW())
             ON CONFLICT (mint_address) DO NOTHING
             "#,
             Uuid::new_v4(),
             mint.mint_address,
             mint.decimals as i16,
             mint.mint_authority,
-            mint.freeze_authority
+            mint.freeze_authority,
+            serde_json::json!({
+                "token_program": mint.token_program.as_str(),
+                "non_transferable": mint.extensions.non_transferable,
+                "permanent_delegate": mint.extensions.permanent_delegate,
+                "name": mint.metadata.as_ref().map(|metadata| &metadata.name),
+                "symbol": mint.metadata.as_ref().map(|metadata| &metadata.symbol),
+                "uri": mint.metadata.as_ref().map(|metadata| &metadata.uri)
+            })
         )
         .execute(&self.pool)
         .await?;
@@ -80,9 +91,10 @@ impl DatabaseService {
             TransactionRecord,
             r#"
             SELECT 
-                id, user_id, transaction_hash, transaction_type, amount,
+                id, user_id, transaction_hash, transaction_type,
+                amount as "amount: TokenAmount",
                 token_address, from_address, to_address, status, 
-                block_number, metadata
+                block_number, gas_used, gas_price, metadata
             FROM blockchain_transactions
             WHERE user_id = $1
             ORDER BY created_at DESC
@@ -102,19 +114,80 @@ impl DatabaseService {
         &self,
         transaction_hash: &str,
         status: &str,
+        block_number: Option<u64>,
     ) -> Result<()> {
-        sqlx::query!(
+        self.record_transaction_outcome(transaction_hash, status, block_number, serde_json::json!({}))
+            .await
+    }
+
+    // Next page of pending transactions, oldest first, keyed on
+    // (created_at, id) so rows settled mid-pass do not shift the window
+    pub async fn get_pending_transactions(
+        &self,
+        after: Option<(chrono::DateTime<chrono::Utc>, Uuid)>,
+        limit: i64,
+    ) -> Result<Vec<PendingTransaction>> {
+        let (after_created_at, after_id) = after.unzip();
+
+        let transactions = sqlx::query_as!(
+            PendingTransaction,
+            r#"
+            SELECT
+                id, transaction_hash,
+                (metadata->>'last_valid_block_height')::BIGINT as last_valid_block_height,
+                created_at as "created_at!"
+            FROM blockchain_transactions
+            WHERE status = 'pending'
+              AND ($1::TIMESTAMPTZ IS NULL OR (created_at, id) > ($1, $2::UUID))
+            ORDER BY created_at ASC, id ASC
+            LIMIT $3
+            "#,
+            after_created_at,
+            after_id,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(transactions)
+    }
+
+    // Sets the final status and merges `metadata` into the stored JSON.
+    // Confirmed rows are final; the first time a row settles, its side
+    // effects are applied in the same database transaction.
+    pub async fn record_transaction_outcome(
+        &self,
+        transaction_hash: &str,
+        status: &str,
+        block_number: Option<u64>,
+        metadata: serde_json::Value,
+    ) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        let settled = sqlx::query_scalar!(
             r#"
-            UPDATE blockchain_transactions 
-            SET status = $1, updated_at = NOW()
-            WHERE transaction_hash = $2
+            UPDATE blockchain_transactions
+            SET status = $1,
+                block_number = COALESCE($2, block_number),
+                metadata = COALESCE(metadata, '{}'::JSONB) || $3,
+                updated_at = NOW()
+            WHERE transaction_hash = $4 AND status <> 'confirmed'
+            RETURNING transaction_type
             "#,
             status,
+            block_number.map(|n| n as i64),
+            metadata,
             transaction_hash
         )
-        .execute(&self.pool)
+        .fetch_optional(&mut *tx)
         .await?;
 
+        if let Some(transaction_type) = settled {
+            settlement::settle(&mut *tx, transaction_hash, &transaction_type, status).await?;
+        }
+
+        tx.commit().await?;
+
         Ok(())
     }
 
@@ -134,18 +207,19 @@ impl DatabaseService {
         Ok(mint)
     }
 
-    pub async fn get_user_token_balances(&self, user_id: Uuid) -> Result<Vec<serde_json::Value>> {
+    pub async fn get_user_token_balances(&self, user_id: Uuid) -> Result<Vec<UserTokenBalance>> {
         let balances = sqlx::query!(
             r#"
             SELECT 
                 tm.mint_address,
                 tm.decimals,
-                SUM(CASE 
+                GREATEST(SUM(CASE 
                     WHEN bt.transaction_type = 'mint' AND bt.to_address IS NOT NULL THEN bt.amount
                     WHEN bt.transaction_type = 'transfer' AND bt.to_address IS NOT NULL THEN bt.amount
                     WHEN bt.transaction_type = 'transfer' AND bt.from_address IS NOT NULL THEN -bt.amount
+                    WHEN bt.transaction_type = 'burn' THEN -bt.amount
                     ELSE 0
-                END) as balance
+                END), 0) as balance
             FROM blockchain_transactions bt
             JOIN token_mints tm ON bt.token_address = tm.mint_address
             WHERE bt.user_id = $1 AND bt.status = 'confirmed'
@@ -156,17 +230,994 @@ impl DatabaseService {
         .fetch_all(&self.pool)
         .await?;
 
-        let results = balances
+        // The history only covers transactions made through the service, so
+        // the sum can go negative; it is clamped to zero in the query
+        balances
             .into_iter()
             .map(|row| {
-                serde_json::json!({
-                    "mint_address": row.mint_address,
-                    "decimals": row.decimals,
-                    "balance": row.balance.unwrap_or(0.0)
+                let balance = match row.balance {
+                    Some(balance) => TokenAmount::from_decimal_with_scale(&balance, row.decimals as u8)?,
+                    None => TokenAmount::new(0, row.decimals as u8),
+                };
+
+                Ok(UserTokenBalance {
+                    mint_address: row.mint_address,
+                    balance,
                 })
             })
-            .collect();
+            .collect()
+    }
+
+    pub async fn get_primary_wallet(&self, user_id: Uuid) -> Result<Option<String>> {
+        let wallet = sqlx::query_scalar!(
+            r#"
+            SELECT wallet_address FROM user_wallets
+            WHERE user_id = $1 AND is_primary = TRUE
+            ORDER BY created_at ASC
+            LIMIT 1
+            "#,
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(wallet)
+    }
+
+    // Claims the user's badge for `mint_address`. Returns false when the user
+    // already has one, or one is being issued. A claim whose mint outcome was
+    // never learned is settled once its transaction row is.
+    pub async fn claim_membership(
+        &self,
+        user_id: Uuid,
+        mint_address: &str,
+        wallet_address: &str,
+    ) -> Result<bool> {
+        let inserted = sqlx::query!(
+            r#"
+            INSERT INTO token_memberships (id, user_id, mint_address, wallet_address, created_at)
+            VALUES ($1, $2, $3, $4, NOW())
+            ON CONFLICT (user_id, mint_address) DO NOTHING
+            "#,
+            Uuid::new_v4(),
+            user_id,
+            mint_address,
+            wallet_address
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(inserted.rows_affected() == 1)
+    }
+
+    pub async fn complete_membership(
+        &self,
+        user_id: Uuid,
+        mint_address: &str,
+        transaction_hash: &str,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE token_memberships
+            SET transaction_hash = $1
+            WHERE user_id = $2 AND mint_address = $3
+            "#,
+            transaction_hash,
+            user_id,
+            mint_address
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    // Frees a claim whose badge was definitely never minted
+    pub async fn release_membership(&self, user_id: Uuid, mint_address: &str) -> Result<()> {
+        sqlx::query!(
+            r#"
+            DELETE FROM token_memberships
+            WHERE user_id = $1 AND mint_address = $2
+            "#,
+            user_id,
+            mint_address
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    // Hides a mint whose creating transaction never landed
+    pub async fn deactivate_mint(&self, mint_address: &str) -> Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE token_mints SET is_active = FALSE, updated_at = NOW()
+            WHERE mint_address = $1
+            "#,
+            mint_address
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_event_registration(&self, registration_id: Uuid) -> Result<Option<EventRegistration>> {
+        let registration = sqlx::query_as!(
+            EventRegistration,
+            r#"
+            SELECT er.id, er.event_id, er.user_id, e.title as event_title
+            FROM event_registrations er
+            JOIN events e ON e.id = er.event_id
+            WHERE er.id = $1
+            "#,
+            registration_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(registration)
+    }
+
+    // Claims the ticket for a registration. Returns the new ticket id, or
+    // `None` when a ticket was already issued or is being issued.
+    pub async fn claim_ticket(
+        &self,
+        registration: &EventRegistration,
+        wallet_address: &str,
+    ) -> Result<Option<Uuid>> {
+        let ticket_id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO event_tickets (id, registration_id, event_id, user_id, wallet_address, status, created_at)
+            VALUES ($1, $2, $3, $4, $5, 'issuing', NOW())
+            ON CONFLICT (registration_id) DO NOTHING
+            RETURNING id
+            "#,
+            Uuid::new_v4(),
+            registration.id,
+            registration.event_id,
+            registration.user_id,
+            wallet_address
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(ticket_id)
+    }
+
+    pub async fn set_ticket_mint(&self, ticket_id: Uuid, mint_address: &str) -> Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE event_tickets SET mint_address = $1, updated_at = NOW()
+            WHERE id = $2
+            "#,
+            mint_address,
+            ticket_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn complete_ticket_issue(&self, ticket_id: Uuid, signature: &str) -> Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE event_tickets SET status = 'issued', issue_signature = $1, updated_at = NOW()
+            WHERE id = $2
+            "#,
+            signature,
+            ticket_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    // Frees a registration whose ticket was definitely never minted
+    pub async fn release_ticket(&self, ticket_id: Uuid) -> Result<()> {
+        sqlx::query!(
+            r#"
+            DELETE FROM event_tickets WHERE id = $1 AND status = 'issuing'
+            "#,
+            ticket_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_ticket_by_mint(&self, mint_address: &str) -> Result<Option<TicketRecord>> {
+        let ticket = sqlx::query_as!(
+            TicketRecord,
+            r#"
+            SELECT id, registration_id, event_id, user_id
+            FROM event_tickets
+            WHERE mint_address = $1
+            "#,
+            mint_address
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(ticket)
+    }
+
+    // Moves an issued ticket to `redeeming`; false if it is not redeemable,
+    // which also stops two check-ins of the same ticket racing
+    pub async fn begin_ticket_redemption(&self, ticket_id: Uuid, wallet_address: &str) -> Result<bool> {
+        let updated = sqlx::query!(
+            r#"
+            UPDATE event_tickets
+            SET status = 'redeeming', redeemed_wallet = $1, updated_at = NOW()
+            WHERE id = $2 AND status = 'issued'
+            "#,
+            wallet_address,
+            ticket_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(updated.rows_affected() == 1)
+    }
+
+    pub async fn complete_ticket_redemption(&self, ticket_id: Uuid, signature: &str) -> Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE event_tickets
+            SET status = 'redeemed', redeem_signature = $1, redeemed_at = NOW(), updated_at = NOW()
+            WHERE id = $2
+            "#,
+            signature,
+            ticket_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn cancel_ticket_redemption(&self, ticket_id: Uuid) -> Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE event_tickets
+            SET status = 'issued', redeemed_wallet = NULL, updated_at = NOW()
+            WHERE id = $1 AND status = 'redeeming'
+            "#,
+            ticket_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn store_collection(
+        &self,
+        mint_address: &str,
+        metadata: &TokenMetadata,
+        created_by: Uuid,
+    ) -> Result<Uuid> {
+        let collection_id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO nft_collections (id, mint_address, name, symbol, uri, created_by, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, NOW())
+            RETURNING id
+            "#,
+            Uuid::new_v4(),
+            mint_address,
+            metadata.name,
+            metadata.symbol,
+            metadata.uri,
+            created_by
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(collection_id)
+    }
+
+    // Drops a collection whose creating transaction definitely failed
+    pub async fn delete_collection(&self, collection_id: Uuid) -> Result<()> {
+        sqlx::query!(
+            r#"
+            DELETE FROM nft_collections WHERE id = $1
+            "#,
+            collection_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_collection(&self, mint_address: &str) -> Result<Option<CollectionRecord>> {
+        let collection = sqlx::query_as!(
+            CollectionRecord,
+            r#"
+            SELECT id, mint_address, name, symbol, uri
+            FROM nft_collections
+            WHERE mint_address = $1
+            "#,
+            mint_address
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(collection)
+    }
+
+    // Records an item against the transaction that verifies it; an item
+    // that is verified again keeps its row and takes the new transaction
+    pub async fn store_collection_item(
+        &self,
+        collection_id: Uuid,
+        mint_address: &str,
+        owner_address: Option<&str>,
+        user_id: Option<Uuid>,
+        metadata: &TokenMetadata,
+        transaction_hash: &str,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO nft_collection_items (
+                id, collection_id, mint_address, owner_address, user_id, name, symbol, uri,
+                transaction_hash, created_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, NOW())
+            ON CONFLICT (mint_address) DO UPDATE
+            SET transaction_hash = EXCLUDED.transaction_hash
+            WHERE nft_collection_items.collection_id = EXCLUDED.collection_id
+            "#,
+            Uuid::new_v4(),
+            collection_id,
+            mint_address,
+            owner_address,
+            user_id,
+            metadata.name,
+            metadata.symbol,
+            metadata.uri,
+            transaction_hash
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    // Drops an item whose minting transaction definitely failed
+    pub async fn delete_collection_item(&self, mint_address: &str) -> Result<()> {
+        sqlx::query!(
+            r#"
+            DELETE FROM nft_collection_items WHERE mint_address = $1 AND NOT verified
+            "#,
+            mint_address
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_collection_items(
+        &self,
+        collection_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<CollectionItem>> {
+        let items = sqlx::query_as!(
+            CollectionItem,
+            r#"
+            SELECT mint_address, owner_address, user_id, name, symbol, uri, verified, created_at
+            FROM nft_collection_items
+            WHERE collection_id = $1
+            ORDER BY created_at
+            LIMIT $2 OFFSET $3
+            "#,
+            collection_id,
+            limit,
+            offset
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(items)
+    }
+
+    pub async fn create_airdrop_job(
+        &self,
+        mint_address: &str,
+        created_by: Uuid,
+        fees: &PriorityFeeOptions,
+        recipients: &[(String, TokenAmount)],
+        total_amount: TokenAmount,
+    ) -> Result<Uuid> {
+        let mut tx = self.pool.begin().await?;
+
+        let job_id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO airdrop_jobs (
+                id, mint_address, decimals, created_by, status, fees, total_recipients, total_amount, created_at
+            ) VALUES ($1, $2, $3, $4, 'running', $5, $6, $7, NOW())
+            RETURNING id
+            "#,
+            Uuid::new_v4(),
+            mint_address,
+            total_amount.decimals() as i16,
+            created_by,
+            serde_json::to_value(fees).unwrap_or_default(),
+            recipients.len() as i32,
+            total_amount.to_decimal()
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let positions: Vec<i32> = (0..recipients.len() as i32).collect();
+        let destinations: Vec<String> = recipients.iter().map(|(destination, _)| destination.clone()).collect();
+        let amounts: Vec<_> = recipients.iter().map(|(_, amount)| amount.to_decimal()).collect();
 
-        Ok(results)
+        sqlx::query!(
+            r#"
+            INSERT INTO airdrop_recipients (job_id, position, destination, amount)
+            SELECT $1, * FROM UNNEST($2::INT4[], $3::TEXT[], $4::NUMERIC[])
+            "#,
+            job_id,
+            &positions,
+            &destinations,
+            &amounts
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(job_id)
+    }
+
+    pub async fn get_airdrop_job(&self, job_id: Uuid) -> Result<Option<AirdropJob>> {
+        let job = sqlx::query_as!(
+            AirdropJob,
+            r#"
+            SELECT id, mint_address, created_by, status, fees
+            FROM airdrop_jobs
+            WHERE id = $1
+            "#,
+            job_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(job)
+    }
+
+    pub async fn get_running_airdrop_jobs(&self) -> Result<Vec<Uuid>> {
+        let job_ids = sqlx::query_scalar!(
+            r#"
+            SELECT id FROM airdrop_jobs WHERE status = 'running' ORDER BY created_at
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(job_ids)
+    }
+
+    pub async fn get_airdrop_job_status(&self, job_id: Uuid) -> Result<Option<AirdropJobStatus>> {
+        let status = sqlx::query_as!(
+            AirdropJobStatus,
+            r#"
+            SELECT
+                aj.id, aj.mint_address, aj.status, aj.total_recipients,
+                aj.total_amount as "total_amount: TokenAmount",
+                COUNT(*) FILTER (WHERE ar.status = 'pending') as "pending!",
+                COUNT(*) FILTER (WHERE ar.status = 'sending') as "sending!",
+                COUNT(*) FILTER (WHERE ar.status = 'confirmed') as "confirmed!",
+                COUNT(*) FILTER (WHERE ar.status = 'failed') as "failed!",
+                aj.created_at as "created_at!", aj.completed_at
+            FROM airdrop_jobs aj
+            LEFT JOIN airdrop_recipients ar ON ar.job_id = aj.id
+            WHERE aj.id = $1
+            GROUP BY aj.id
+            "#,
+            job_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(status)
+    }
+
+    pub async fn get_airdrop_recipients(
+        &self,
+        job_id: Uuid,
+        status: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<AirdropRecipientRecord>> {
+        let recipients = sqlx::query_as!(
+            AirdropRecipientRecord,
+            r#"
+            SELECT
+                id, position, destination, amount as "amount: TokenAmount",
+                status, attempts, transaction_hash, error
+            FROM airdrop_recipients
+            WHERE job_id = $1 AND ($2::TEXT IS NULL OR status = $2)
+            ORDER BY position
+            LIMIT $3 OFFSET $4
+            "#,
+            job_id,
+            status,
+            limit,
+            offset
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(recipients)
+    }
+
+    // Transactions of a job that recipients are still waiting on, with the
+    // status of their `blockchain_transactions` row if there is one
+    pub async fn get_in_flight_airdrop_transactions(
+        &self,
+        job_id: Uuid,
+    ) -> Result<Vec<(String, Option<String>)>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT DISTINCT ar.transaction_hash as "transaction_hash!", bt.status as "status?"
+            FROM airdrop_recipients ar
+            LEFT JOIN blockchain_transactions bt ON bt.transaction_hash = ar.transaction_hash
+            WHERE ar.job_id = $1 AND ar.status = 'sending'
+            "#,
+            job_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(|row| (row.transaction_hash, row.status)).collect())
+    }
+
+    // Recipients are marked before their transaction is sent; returns how
+    // many were still pending and so got marked
+    pub async fn mark_airdrop_sending(&self, recipient_ids: &[Uuid], transaction_hash: &str) -> Result<u64> {
+        let updated = sqlx::query!(
+            r#"
+            UPDATE airdrop_recipients
+            SET status = 'sending', transaction_hash = $1, updated_at = NOW()
+            WHERE id = ANY($2) AND status = 'pending'
+            "#,
+            transaction_hash,
+            recipient_ids
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(updated.rows_affected())
+    }
+
+    pub async fn confirm_airdrop_transaction(&self, transaction_hash: &str) -> Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE airdrop_recipients
+            SET status = 'confirmed', error = NULL, updated_at = NOW()
+            WHERE transaction_hash = $1 AND status = 'sending'
+            "#,
+            transaction_hash
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    // Puts recipients that definitely did not receive their tokens back in
+    // the queue. A counted attempt moves a recipient that has used up
+    // `max_attempts` to `failed` instead; every retry path goes through here
+    // so that rule is applied the same way everywhere.
+    pub async fn retry_airdrop_recipients(
+        &self,
+        recipients: AirdropRetry<'_>,
+        error: &str,
+        count_attempt: bool,
+        max_attempts: i32,
+    ) -> Result<()> {
+        let (transaction_hash, recipient_ids) = match recipients {
+            AirdropRetry::InFlight(transaction_hash) => (Some(transaction_hash), &[][..]),
+            AirdropRetry::Unbuilt(recipient_ids) => (None, recipient_ids),
+        };
+
+        sqlx::query!(
+            r#"
+            UPDATE airdrop_recipients
+            SET attempts = attempts + $3,
+                status = CASE WHEN attempts + $3 >= $4 THEN 'failed' ELSE 'pending' END,
+                error = $5,
+                updated_at = NOW()
+            WHERE (transaction_hash = $1 AND status = 'sending')
+               OR (id = ANY($2) AND status = 'pending')
+            "#,
+            transaction_hash,
+            recipient_ids,
+            count_attempt as i32,
+            max_attempts,
+            error
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_pending_airdrop_recipients(
+        &self,
+        job_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<AirdropRecipientRecord>> {
+        self.get_airdrop_recipients(job_id, Some("pending"), limit, 0).await
+    }
+
+    // Completes a job once no recipient is pending or in flight; false if
+    // some still are
+    pub async fn complete_airdrop_job(&self, job_id: Uuid) -> Result<bool> {
+        let updated = sqlx::query!(
+            r#"
+            UPDATE airdrop_jobs
+            SET status = 'completed', completed_at = NOW(), updated_at = NOW()
+            WHERE id = $1 AND status = 'running'
+              AND NOT EXISTS (
+                  SELECT 1 FROM airdrop_recipients
+                  WHERE job_id = $1 AND status IN ('pending', 'sending')
+              )
+            "#,
+            job_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(updated.rows_affected() == 1)
+    }
+
+    // Entries are (wallet, amount, proof) in leaf order
+    pub async fn create_campaign(
+        &self,
+        request: &CreateCampaignRequest,
+        merkle_root: &str,
+        created_by: Uuid,
+        entries: &[(String, TokenAmount, Vec<String>)],
+        total_amount: TokenAmount,
+    ) -> Result<CampaignRecord> {
+        let mut tx = self.pool.begin().await?;
+
+        let campaign = sqlx::query_as!(
+            CampaignRecord,
+            r#"
+            INSERT INTO airdrop_campaigns (
+                id, name, mint_address, distribution, merkle_root, total_entries, total_amount,
+                created_by, created_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, NOW())
+            RETURNING
+                id, name, mint_address, distribution, merkle_root, total_entries,
+                total_amount as "total_amount: TokenAmount", is_active, created_at as "created_at!"
+            "#,
+            Uuid::new_v4(),
+            request.name,
+            request.mint_address,
+            request.distribution.as_str(),
+            merkle_root,
+            entries.len() as i32,
+            total_amount.to_decimal(),
+            created_by
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let positions: Vec<i32> = (0..entries.len() as i32).collect();
+        let wallets: Vec<String> = entries.iter().map(|(wallet, _, _)| wallet.clone()).collect();
+        let amounts: Vec<_> = entries.iter().map(|(_, amount, _)| amount.to_decimal()).collect();
+        let proofs: Vec<serde_json::Value> = entries.iter().map(|(_, _, proof)| serde_json::json!(proof)).collect();
+
+        sqlx::query!(
+            r#"
+            INSERT INTO airdrop_campaign_entries (campaign_id, position, wallet_address, amount, proof)
+            SELECT $1, * FROM UNNEST($2::INT4[], $3::TEXT[], $4::NUMERIC[], $5::JSONB[])
+            "#,
+            campaign.id,
+            &positions,
+            &wallets,
+            &amounts,
+            &proofs
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(campaign)
+    }
+
+    pub async fn get_campaign(&self, campaign_id: Uuid) -> Result<Option<CampaignRecord>> {
+        let campaign = sqlx::query_as!(
+            CampaignRecord,
+            r#"
+            SELECT
+                id, name, mint_address, distribution, merkle_root, total_entries,
+                total_amount as "total_amount: TokenAmount", is_active, created_at as "created_at!"
+            FROM airdrop_campaigns
+            WHERE id = $1
+            "#,
+            campaign_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(campaign)
+    }
+
+    pub async fn get_campaign_entry(
+        &self,
+        campaign_id: Uuid,
+        wallet_address: &str,
+    ) -> Result<Option<CampaignEntryRecord>> {
+        let entry = sqlx::query_as!(
+            CampaignEntryRecord,
+            r#"
+            SELECT id, position, wallet_address, amount as "amount: TokenAmount", proof, status
+            FROM airdrop_campaign_entries
+            WHERE campaign_id = $1 AND wallet_address = $2
+            "#,
+            campaign_id,
+            wallet_address
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(entry)
+    }
+
+    pub async fn user_has_wallet(&self, user_id: Uuid, wallet_address: &str) -> Result<bool> {
+        let found = sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM user_wallets WHERE user_id = $1 AND wallet_address = $2
+            ) as "found!"
+            "#,
+            user_id,
+            wallet_address
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(found)
+    }
+
+    // Sets the claimed flag's in-between state; false if the entry was
+    // already claimed or is being claimed
+    pub async fn begin_campaign_claim(&self, entry_id: Uuid) -> Result<bool> {
+        let updated = sqlx::query!(
+            r#"
+            UPDATE airdrop_campaign_entries
+            SET status = 'claiming', updated_at = NOW()
+            WHERE id = $1 AND status = 'unclaimed'
+            "#,
+            entry_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(updated.rows_affected() == 1)
+    }
+
+    pub async fn complete_campaign_claim(&self, entry_id: Uuid, signature: &str) -> Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE airdrop_campaign_entries
+            SET status = 'claimed', claim_signature = $1, claimed_at = NOW(), updated_at = NOW()
+            WHERE id = $2
+            "#,
+            signature,
+            entry_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    // Reopens an entry whose payout definitely never happened
+    pub async fn release_campaign_claim(&self, entry_id: Uuid) -> Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE airdrop_campaign_entries
+            SET status = 'unclaimed', updated_at = NOW()
+            WHERE id = $1 AND status = 'claiming'
+            "#,
+            entry_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_user_role(&self, user_id: Uuid) -> Result<Option<String>> {
+        let role = sqlx::query_scalar!(
+            r#"
+            SELECT role FROM user_profiles WHERE id = $1
+            "#,
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(role)
+    }
+
+    // Claims the caller's idempotency key for this request. Returns the
+    // existing record when the key has been used before, or `None` if this
+    // call now owns it.
+    pub async fn claim_idempotency_key(
+        &self,
+        user_id: Uuid,
+        endpoint: &str,
+        idempotency_key: &str,
+        request_hash: &str,
+    ) -> Result<Option<IdempotencyRecord>> {
+        let inserted = sqlx::query!(
+            r#"
+            INSERT INTO idempotency_keys (user_id, endpoint, idempotency_key, request_hash, created_at)
+            VALUES ($1, $2, $3, $4, NOW())
+            ON CONFLICT (user_id, endpoint, idempotency_key) DO NOTHING
+            "#,
+            user_id,
+            endpoint,
+            idempotency_key,
+            request_hash
+        )
+        .execute(&self.pool)
+        .await?;
+
+        if inserted.rows_affected() == 1 {
+            return Ok(None);
+        }
+
+        let existing = sqlx::query_as!(
+            IdempotencyRecord,
+            r#"
+            SELECT request_hash, response
+            FROM idempotency_keys
+            WHERE user_id = $1 AND endpoint = $2 AND idempotency_key = $3
+            "#,
+            user_id,
+            endpoint,
+            idempotency_key
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(Some(existing))
+    }
+
+    pub async fn complete_idempotency_key(
+        &self,
+        user_id: Uuid,
+        endpoint: &str,
+        idempotency_key: &str,
+        response: &serde_json::Value,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE idempotency_keys
+            SET response = $1, completed_at = NOW()
+            WHERE user_id = $2 AND endpoint = $3 AND idempotency_key = $4
+            "#,
+            response,
+            user_id,
+            endpoint,
+            idempotency_key
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    // Frees a key whose request failed so the client can retry with it
+    pub async fn release_idempotency_key(
+        &self,
+        user_id: Uuid,
+        endpoint: &str,
+        idempotency_key: &str,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+            DELETE FROM idempotency_keys
+            WHERE user_id = $1 AND endpoint = $2 AND idempotency_key = $3 AND response IS NULL
+            "#,
+            user_id,
+            endpoint,
+            idempotency_key
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    // Stores a transaction prepared for wallet signing, dropping any that have
+    // expired so the table only holds what can still be submitted
+    pub async fn insert_prepared_transaction(
+        &self,
+        message_hash: &str,
+        prepared: &PreparedTransactionRow,
+        ttl: Duration,
+    ) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!("DELETE FROM prepared_transactions WHERE expires_at <= NOW()")
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO prepared_transactions (
+                message_hash, message, record, last_valid_block_height,
+                compute_unit_limit, compute_unit_price, created_at, expires_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, NOW(), NOW() + make_interval(secs => $7))
+            ON CONFLICT (message_hash) DO UPDATE SET
+                message = EXCLUDED.message,
+                record = EXCLUDED.record,
+                last_valid_block_height = EXCLUDED.last_valid_block_height,
+                compute_unit_limit = EXCLUDED.compute_unit_limit,
+                compute_unit_price = EXCLUDED.compute_unit_price,
+                created_at = EXCLUDED.created_at,
+                expires_at = EXCLUDED.expires_at
+            "#,
+            message_hash,
+            prepared.message,
+            prepared.record,
+            prepared.last_valid_block_height,
+            prepared.compute_unit_limit,
+            prepared.compute_unit_price,
+            ttl.as_secs_f64()
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    pub async fn get_prepared_transaction(
+        &self,
+        message_hash: &str,
+    ) -> Result<Option<PreparedTransactionRow>> {
+        let row = sqlx::query_as!(
+            PreparedTransactionRow,
+            r#"
+            SELECT message, record, last_valid_block_height, compute_unit_limit, compute_unit_price
+            FROM prepared_transactions
+            WHERE message_hash = $1 AND expires_at > NOW()
+            "#,
+            message_hash
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row)
+    }
+
+    pub async fn delete_prepared_transaction(&self, message_hash: &str) -> Result<()> {
+        sqlx::query!(
+            "DELETE FROM prepared_transactions WHERE message_hash = $1",
+            message_hash
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
     }
 }
@@ -15,9 +15,28 @@ pub enum AppError {
     InvalidInput(String),
     NotFound(String),
     Unauthorized,
+    Forbidden(String),
+    Conflict(String),
+    TransactionFailed(String),
     Internal(String),
 }
 
+impl AppError {
+    // Errors raised before anything could have reached the chain, or for a
+    // transaction the cluster rejected, so the request had no effect
+    pub fn had_no_effect(&self) -> bool {
+        matches!(
+            self,
+            AppError::InvalidInput(_)
+                | AppError::NotFound(_)
+                | AppError::Unauthorized
+                | AppError::Forbidden(_)
+                | AppError::Conflict(_)
+                | AppError::TransactionFailed(_)
+        )
+    }
+}
+
 impl From<sqlx::Error> for AppError {
     fn from(err: sqlx::Error) -> Self {
         Self::Database(err)
@@ -66,6 +85,15 @@ impl IntoResponse for AppError {
             AppError::Unauthorized => {
                 (StatusCode::UNAUTHORIZED, "Unauthorized".to_string(), Some("UNAUTHORIZED"))
             }
+            AppError::Forbidden(msg) => {
+                (StatusCode::FORBIDDEN, msg, Some("FORBIDDEN"))
+            }
+            AppError::Conflict(msg) => {
+                (StatusCode::CONFLICT, msg, Some("CONFLICT"))
+            }
+            AppError::TransactionFailed(msg) => {
+                (StatusCode::BAD_REQUEST, msg, Some("TRANSACTION_FAILED"))
+            }
             AppError::Internal(msg) => {
                 tracing::error!("Internal error: {}", msg);
                 (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error".to_string(), Some("INTERNAL_ERROR"))
@@ -0,0 +1,89 @@
+use crate::{
+    auth::AuthUser,
+    error::{AppError, Result},
+    AppState,
+};
+use anyhow::anyhow;
+use axum::http::HeaderMap;
+use serde::{de::DeserializeOwned, Serialize};
+use std::future::Future;
+
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+// The header wins over the body field when both are sent
+pub fn idempotency_key(headers: &HeaderMap, body_key: Option<&str>) -> Option<String> {
+    headers
+        .get(IDEMPOTENCY_KEY_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .or(body_key)
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+        .map(str::to_string)
+}
+
+// Runs `operation` at most once per (caller, endpoint, key). A replay with the
+// same request body gets the stored response back; a different body is
+// rejected. Keys are scoped to the caller, so one user's key never replays or
+// reveals another user's request.
+pub async fn run_once<R, T, F, Fut>(
+    state: &AppState,
+    user: &AuthUser,
+    endpoint: &str,
+    key: Option<String>,
+    request: &R,
+    operation: F,
+) -> Result<T>
+where
+    R: Serialize,
+    T: Serialize + DeserializeOwned,
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let Some(key) = key else {
+        return operation().await;
+    };
+
+    let body = serde_json::to_vec(request)
+        .map_err(|e| anyhow!("Failed to serialize request: {}", e))?;
+    let request_hash = solana_sdk::hash::hash(&body).to_string();
+
+    if let Some(existing) = state
+        .database
+        .claim_idempotency_key(user.user_id, endpoint, &key, &request_hash)
+        .await?
+    {
+        if existing.request_hash != request_hash {
+            return Err(AppError::Conflict(
+                "Idempotency key was already used with a different request".to_string(),
+            ));
+        }
+
+        return match existing.response {
+            Some(response) => serde_json::from_value(response)
+                .map_err(|e| anyhow!("Failed to decode stored response: {}", e).into()),
+            None => Err(AppError::Conflict(
+                "A request with this idempotency key is in progress or did not complete".to_string(),
+            )),
+        };
+    }
+
+    match operation().await {
+        Ok(result) => {
+            let response = serde_json::to_value(&result)
+                .map_err(|e| anyhow!("Failed to serialize response: {}", e))?;
+            state
+                .database
+                .complete_idempotency_key(user.user_id, endpoint, &key, &response)
+                .await?;
+            Ok(result)
+        }
+        Err(err) => {
+            // Only release the key when the request definitely had no effect
+            // on-chain; otherwise a retry could mint or transfer a second time
+            if err.had_no_effect() {
+                state.database.release_idempotency_key(user.user_id, endpoint, &key).await?;
+            }
+            Err(err)
+        }
+    }
+}
@@ -1,38 +1,60 @@
 use axum::{
-    extract::{Query, State},
-    http::StatusCode,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    middleware,
     response::Json,
     routing::{get, post},
     Router,
 };
+use bigdecimal::BigDecimal;
 use serde::{Deserialize, Serialize};
-use solana_client::rpc_client::RpcClient;
+use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_sdk::{
     commitment_config::CommitmentConfig,
+    hash::Hash,
+    native_token::{lamports_to_sol, sol_to_lamports},
     pubkey::Pubkey,
     signature::{Keypair, Signer},
     system_instruction,
     transaction::Transaction,
 };
-use std::{str::FromStr, sync::Arc};
+use std::{collections::HashSet, str::FromStr, sync::Arc};
 use tower_http::cors::CorsLayer;
 use tracing::{info, warn};
 use uuid::Uuid;
 
+mod airdrop;
+mod amount;
+mod auth;
 mod blockchain;
 mod database;
 mod error;
+mod idempotency;
+mod merkle;
 mod models;
+mod prepared;
+mod reconciler;
+mod settlement;
 
-use blockchain::BlockchainService;
+use airdrop::{AirdropConfig, AirdropRunner};
+use amount::TokenAmount;
+use auth::{ApiKeys, AuthUser, JwtVerifier};
+use blockchain::{BlockchainService, BroadcastOutcome, BuiltTransaction, FreezeAction};
 use database::DatabaseService;
 use error::{AppError, Result};
+use idempotency::{idempotency_key, run_once};
 use models::*;
+use prepared::PreparedTransactions;
+use reconciler::ReconcilerConfig;
 
 #[derive(Clone)]
 pub struct AppState {
     pub blockchain: Arc<BlockchainService>,
     pub database: Arc<DatabaseService>,
+    pub api_keys: Arc<ApiKeys>,
+    pub jwt: Arc<JwtVerifier>,
+    pub prepared: Arc<PreparedTransactions>,
+    pub airdrops: Arc<AirdropRunner>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -65,86 +87,2257 @@ async fn health_check() -> Json<ApiResponse<&'static str>> {
     Json(ApiResponse::success("Solana service is running"))
 }
 
-// Get wallet balance
+fn parse_pubkey_list(list: &str, what: &str) -> Result<Vec<Pubkey>> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(|item| {
+            Pubkey::from_str(item)
+                .map_err(|_| AppError::InvalidInput(format!("Invalid {}: {}", what, item)))
+        })
+        .collect()
+}
+
+// Most wallets accepted by a single `/balances` call
+const MAX_BALANCE_ADDRESSES: usize = 100;
+
+async fn fetch_balances(
+    state: &AppState,
+    addresses: Vec<Pubkey>,
+    mints: Vec<Pubkey>,
+) -> Result<Vec<BalanceResponse>> {
+    let balances = state.blockchain.get_balances(&addresses, &mints).await?;
+
+    Ok(addresses
+        .iter()
+        .zip(balances)
+        .map(|(address, (balance, tokens))| BalanceResponse {
+            address: address.to_string(),
+            balance_lamports: balance,
+            balance_sol: balance as f64 / 1_000_000_000.0,
+            tokens,
+        })
+        .collect())
+}
+
+// Get wallet balance, optionally with token balances for the given mints
 async fn get_balance(
     Query(params): Query<BalanceQuery>,
     State(state): State<AppState>,
-) -> Result<Json<ApiResponse<BalanceResponse>>> {
-    let pubkey = Pubkey::from_str(&params.address)
+) -> Result<Json<ApiResponse<BalanceResponse>>> {
+    let pubkey = Pubkey::from_str(&params.address)
+        .map_err(|_| AppError::InvalidInput("Invalid wallet address".to_string()))?;
+
+    let mints = parse_pubkey_list(params.mint.as_deref().unwrap_or_default(), "mint address")?;
+
+    let balance = fetch_balances(&state, vec![pubkey], mints)
+        .await?
+        .pop()
+        .ok_or_else(|| AppError::Internal("Balance lookup returned no result".to_string()))?;
+
+    Ok(Json(ApiResponse::success(balance)))
+}
+
+// Get balances for several wallets in one call
+async fn get_balances(
+    Query(params): Query<BalancesQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<Vec<BalanceResponse>>>> {
+    let addresses = parse_pubkey_list(&params.addresses, "wallet address")?;
+    if addresses.is_empty() || addresses.len() > MAX_BALANCE_ADDRESSES {
+        return Err(AppError::InvalidInput(format!(
+            "Provide between 1 and {} addresses",
+            MAX_BALANCE_ADDRESSES
+        )));
+    }
+
+    let mints = parse_pubkey_list(params.mints.as_deref().unwrap_or_default(), "mint address")?;
+
+    let balances = fetch_balances(&state, addresses, mints).await?;
+
+    Ok(Json(ApiResponse::success(balances)))
+}
+
+// Create a new token mint
+async fn create_mint(
+    State(state): State<AppState>,
+    user: AuthUser,
+    headers: HeaderMap,
+    Json(payload): Json<CreateMintRequest>,
+) -> Result<Json<ApiResponse<MintResponse>>> {
+    let key = idempotency_key(&headers, payload.idempotency_key.as_deref());
+    let result = run_once(&state, &user, "create_mint", key, &payload, || async {
+        let (built, mut mint) = state.blockchain.build_mint(&payload).await?;
+        let transaction_record = create_mint_transaction_record(&user, &mint, &built);
+
+        let transaction = broadcast_new_mint(&state, &mint, built, &transaction_record).await?;
+        mint.signature = transaction.signature;
+
+        Ok(mint)
+    }).await?;
+
+    Ok(Json(ApiResponse::success(result)))
+}
+
+// Pending row for `built` with the fields every transaction shares; callers
+// fill in their own fields and pass their own `metadata`. `gas_used` is left
+// empty: the compute-unit limit is only what was requested, so it is kept in
+// `metadata` instead.
+fn base_record(
+    built: &BuiltTransaction,
+    user_id: Uuid,
+    transaction_type: &str,
+    metadata: serde_json::Value,
+) -> TransactionRecord {
+    let mut record = TransactionRecord {
+        id: Uuid::new_v4(),
+        user_id,
+        transaction_hash: built.signature(),
+        transaction_type: transaction_type.to_string(),
+        amount: None,
+        token_address: None,
+        from_address: None,
+        to_address: None,
+        status: "pending".to_string(),
+        block_number: None,
+        gas_used: None,
+        gas_price: built.compute_budget.compute_unit_price.map(BigDecimal::from),
+        metadata,
+    };
+
+    record.metadata["compute_unit_limit"] = built.compute_budget.compute_unit_limit.into();
+    record.metadata["last_valid_block_height"] = built.last_valid_block_height.into();
+
+    record
+}
+
+fn mint_transaction_record(
+    payload: &MintTokensRequest,
+    amount: TokenAmount,
+    built: &BuiltTransaction,
+) -> TransactionRecord {
+    TransactionRecord {
+        amount: Some(amount),
+        token_address: Some(payload.mint_address.clone()),
+        to_address: Some(payload.destination_address.clone()),
+        ..base_record(built, payload.user_id, "mint", serde_json::json!({
+            "mint_authority": payload.authority,
+            "amount": amount.raw()
+        }))
+    }
+}
+
+// Creation of a mint, recorded against the user who requested it
+fn create_mint_transaction_record(
+    user: &AuthUser,
+    mint: &MintResponse,
+    built: &BuiltTransaction,
+) -> TransactionRecord {
+    TransactionRecord {
+        token_address: Some(mint.mint_address.clone()),
+        ..base_record(built, user.user_id, "create_mint", serde_json::json!({
+            "decimals": mint.decimals,
+            "mint_authority": mint.mint_authority,
+            "freeze_authority": mint.freeze_authority,
+            "token_program": mint.token_program
+        }))
+    }
+}
+
+fn transfer_transaction_record(
+    payload: &TransferTokensRequest,
+    amount: TokenAmount,
+    built: &BuiltTransaction,
+) -> TransactionRecord {
+    TransactionRecord {
+        amount: Some(amount),
+        token_address: Some(payload.mint_address.clone()),
+        from_address: Some(payload.from_address.clone()),
+        to_address: Some(payload.to_address.clone()),
+        ..base_record(built, payload.user_id, "transfer", serde_json::json!({
+            "owner": payload.owner,
+            "amount": amount.raw()
+        }))
+    }
+}
+
+fn burn_transaction_record(
+    payload: &BurnTokensRequest,
+    amount: TokenAmount,
+    built: &BuiltTransaction,
+) -> TransactionRecord {
+    TransactionRecord {
+        amount: Some(amount),
+        token_address: Some(payload.mint_address.clone()),
+        from_address: Some(payload.owner_address.clone()),
+        ..base_record(built, payload.user_id, "burn", serde_json::json!({
+            "authority": payload.authority,
+            "amount": amount.raw()
+        }))
+    }
+}
+
+fn membership_transaction_record(
+    payload: &GrantMembershipRequest,
+    wallet_address: &str,
+    amount: TokenAmount,
+    built: &BuiltTransaction,
+) -> TransactionRecord {
+    TransactionRecord {
+        amount: Some(amount),
+        token_address: Some(payload.mint_address.clone()),
+        to_address: Some(wallet_address.to_string()),
+        ..base_record(built, payload.user_id, "mint", serde_json::json!({
+            "membership": true,
+            "amount": amount.raw()
+        }))
+    }
+}
+
+fn ticket_transaction_record(
+    registration: &EventRegistration,
+    wallet_address: &str,
+    mint: &Pubkey,
+    built: &BuiltTransaction,
+) -> TransactionRecord {
+    TransactionRecord {
+        amount: Some(TokenAmount::new(1, 0)),
+        token_address: Some(mint.to_string()),
+        to_address: Some(wallet_address.to_string()),
+        ..base_record(built, registration.user_id, "mint", serde_json::json!({
+            "ticket": true,
+            "event_id": registration.event_id,
+            "registration_id": registration.id,
+            "amount": 1
+        }))
+    }
+}
+
+// Minting of a collection NFT, or of an item into `collection`
+fn collection_mint_transaction_record(
+    user_id: Uuid,
+    collection: Option<&Pubkey>,
+    mint: &Pubkey,
+    owner_address: &str,
+    built: &BuiltTransaction,
+) -> TransactionRecord {
+    TransactionRecord {
+        amount: Some(TokenAmount::new(1, 0)),
+        token_address: Some(mint.to_string()),
+        to_address: Some(owner_address.to_string()),
+        ..base_record(built, user_id, "mint", serde_json::json!({
+            "nft": true,
+            "collection": collection.map(|key| key.to_string()),
+            "is_collection": collection.is_none(),
+            "amount": 1
+        }))
+    }
+}
+
+fn verify_collection_transaction_record(
+    user: &AuthUser,
+    payload: &VerifyCollectionItemRequest,
+    built: &BuiltTransaction,
+) -> TransactionRecord {
+    TransactionRecord {
+        token_address: Some(payload.mint_address.clone()),
+        ..base_record(built, user.user_id, "verify_collection", serde_json::json!({
+            "collection": payload.collection_address
+        }))
+    }
+}
+
+// A campaign payout, recorded as the mint or transfer it is
+fn campaign_claim_transaction_record(
+    user: &AuthUser,
+    campaign: &CampaignRecord,
+    entry: &CampaignEntryRecord,
+    payer: &Pubkey,
+    built: &BuiltTransaction,
+) -> TransactionRecord {
+    let is_transfer = campaign.distribution == CampaignDistribution::Transfer.as_str();
+
+    TransactionRecord {
+        amount: Some(entry.amount),
+        token_address: Some(campaign.mint_address.clone()),
+        from_address: is_transfer.then(|| payer.to_string()),
+        to_address: Some(entry.wallet_address.clone()),
+        ..base_record(built, user.user_id, &campaign.distribution, serde_json::json!({
+            "campaign_id": campaign.id,
+            "campaign_index": entry.position,
+            "amount": entry.amount.raw()
+        }))
+    }
+}
+
+// Freeze and thaw actions are recorded against the moderator who took them
+fn freeze_transaction_record(
+    user: &AuthUser,
+    action: FreezeAction,
+    payload: &FreezeAccountRequest,
+    freeze_authority: &str,
+    built: &BuiltTransaction,
+) -> TransactionRecord {
+    TransactionRecord {
+        token_address: Some(payload.mint_address.clone()),
+        to_address: Some(payload.owner_address.clone()),
+        ..base_record(built, user.user_id, action.as_str(), serde_json::json!({
+            "freeze_authority": freeze_authority,
+            "reason": payload.reason
+        }))
+    }
+}
+
+// `token_mints` is only updated from this record once it is confirmed
+fn set_authority_transaction_record(
+    user: &AuthUser,
+    payload: &SetAuthorityRequest,
+    current_authority: &str,
+    built: &BuiltTransaction,
+) -> TransactionRecord {
+    TransactionRecord {
+        token_address: Some(payload.mint_address.clone()),
+        from_address: Some(current_authority.to_string()),
+        to_address: payload.new_authority.clone(),
+        ..base_record(built, user.user_id, "set_authority", serde_json::json!({
+            "authority_type": payload.authority_type.as_str(),
+            "current_authority": current_authority,
+            "new_authority": payload.new_authority
+        }))
+    }
+}
+
+// Decimals of a mint, from its `token_mints` row when this service created it
+// and from the chain otherwise
+async fn mint_decimals(state: &AppState, mint: &Pubkey) -> Result<u8> {
+    if let Some(record) = state.database.get_mint_info(&mint.to_string()).await? {
+        return Ok(record.decimals as u8);
+    }
+
+    Ok(state.blockchain.get_mint(mint).await?.decimals)
+}
+
+// Resolves a request amount given either as raw base units or as a UI string
+// in the mint's decimals
+async fn token_amount(
+    state: &AppState,
+    mint: &Pubkey,
+    raw: Option<u64>,
+    ui_amount: Option<&str>,
+) -> Result<TokenAmount> {
+    let decimals = mint_decimals(state, mint).await?;
+
+    parse_token_amount(decimals, raw, ui_amount)
+}
+
+fn parse_token_amount(decimals: u8, raw: Option<u64>, ui_amount: Option<&str>) -> Result<TokenAmount> {
+    let amount = match (raw, ui_amount) {
+        (Some(raw), None) => TokenAmount::new(raw, decimals),
+        (None, Some(ui_amount)) => TokenAmount::from_ui_str(ui_amount, decimals)?,
+        _ => {
+            return Err(AppError::InvalidInput(
+                "Provide exactly one of amount or ui_amount".to_string(),
+            ))
+        }
+    };
+
+    if amount.raw() == 0 {
+        return Err(AppError::InvalidInput("Amount must be greater than zero".to_string()));
+    }
+
+    Ok(amount)
+}
+
+// `token_mints.metadata` picks up the new values once this is confirmed
+fn metadata_transaction_record(
+    user: &AuthUser,
+    payload: &UpdateMetadataRequest,
+    metadata: &TokenMetadata,
+    built: &BuiltTransaction,
+) -> TransactionRecord {
+    TransactionRecord {
+        token_address: Some(payload.mint_address.clone()),
+        ..base_record(built, user.user_id, "update_metadata", serde_json::json!({
+            "name": metadata.name,
+            "symbol": metadata.symbol,
+            "uri": metadata.uri
+        }))
+    }
+}
+
+fn sol_amount_lamports(amount: &SolAmount) -> Result<u64> {
+    let lamports = match (amount.amount_lamports, amount.amount_sol) {
+        (Some(lamports), None) => lamports,
+        (None, Some(sol)) if sol.is_finite() && sol > 0.0 => sol_to_lamports(sol),
+        (None, Some(_)) => {
+            return Err(AppError::InvalidInput("amount_sol must be a positive number".to_string()))
+        }
+        _ => {
+            return Err(AppError::InvalidInput(
+                "Provide exactly one of amount_lamports or amount_sol".to_string(),
+            ))
+        }
+    };
+
+    if lamports == 0 {
+        return Err(AppError::InvalidInput("Amount must be greater than zero".to_string()));
+    }
+
+    Ok(lamports)
+}
+
+fn sol_transfer_record(
+    user_id: Uuid,
+    from_address: String,
+    to_address: String,
+    lamports: u64,
+    built: &BuiltTransaction,
+) -> TransactionRecord {
+    TransactionRecord {
+        amount: Some(TokenAmount::lamports(lamports)),
+        from_address: Some(from_address),
+        to_address: Some(to_address),
+        ..base_record(built, user_id, "sol_transfer", serde_json::json!({
+            "amount_lamports": lamports,
+            "amount_sol": lamports_to_sol(lamports)
+        }))
+    }
+}
+
+// Records the transaction as pending before it is broadcast, then moves it to
+// confirmed or failed once the outcome is known. Every broadcast attempt is
+// kept in the record's metadata; anything still undecided is left pending for
+// the reconciler to settle.
+async fn broadcast_recorded(
+    state: &AppState,
+    mut built: BuiltTransaction,
+    transaction_record: &TransactionRecord,
+) -> Result<TransactionResponse> {
+    let mut record = transaction_record.clone();
+    let mut attempts = Vec::new();
+    let mut rebuilds = 0;
+
+    state.database.store_transaction(&record).await?;
+
+    loop {
+        let outcome = match state.blockchain.broadcast_until_expired(&built).await {
+            Ok(outcome) => outcome,
+            Err(err) => return settle_after_error(state, &record, err).await,
+        };
+
+        let signature = built.signature();
+
+        match outcome {
+            BroadcastOutcome::Confirmed { slot } => {
+                attempts.push(serde_json::json!({ "signature": signature, "outcome": "confirmed" }));
+                state
+                    .database
+                    .record_transaction_outcome(
+                        &signature,
+                        "confirmed",
+                        Some(slot),
+                        serde_json::json!({ "attempts": attempts }),
+                    )
+                    .await?;
+
+                return Ok(TransactionResponse {
+                    signature,
+                    slot: Some(slot),
+                    status: "confirmed".to_string(),
+                });
+            }
+            BroadcastOutcome::Failed { slot } => {
+                attempts.push(serde_json::json!({ "signature": signature, "outcome": "failed" }));
+                state
+                    .database
+                    .record_transaction_outcome(
+                        &signature,
+                        "failed",
+                        slot,
+                        serde_json::json!({ "attempts": attempts }),
+                    )
+                    .await?;
+
+                return Err(AppError::TransactionFailed(format!(
+                    "Transaction {} failed on-chain",
+                    signature
+                )));
+            }
+            BroadcastOutcome::Expired => {
+                attempts.push(serde_json::json!({ "signature": signature, "outcome": "expired" }));
+
+                let next = if rebuilds < state.blockchain.retry_policy().max_rebuilds {
+                    state.blockchain.rebuild(&built).await?
+                } else {
+                    None
+                };
+
+                let Some(next) = next else {
+                    state
+                        .database
+                        .record_transaction_outcome(
+                            &signature,
+                            "failed",
+                            None,
+                            serde_json::json!({
+                                "attempts": attempts,
+                                "failure_reason": "blockhash_expired"
+                            }),
+                        )
+                        .await?;
+
+                    return Err(AppError::TransactionFailed(format!(
+                        "Transaction {} expired before it was confirmed",
+                        signature
+                    )));
+                };
+
+                state
+                    .database
+                    .record_transaction_outcome(
+                        &signature,
+                        "failed",
+                        None,
+                        serde_json::json!({
+                            "attempts": attempts,
+                            "failure_reason": "blockhash_expired",
+                            "superseded_by": next.signature()
+                        }),
+                    )
+                    .await?;
+
+                // The rebuilt transaction has a new signature, so it gets its
+                // own pending row before it is sent
+                record.id = Uuid::new_v4();
+                record.transaction_hash = next.signature();
+                record.metadata["last_valid_block_height"] = next.last_valid_block_height.into();
+                record.metadata["replaces"] = signature.into();
+                state.database.store_transaction(&record).await?;
+
+                built = next;
+                rebuilds += 1;
+            }
+        }
+    }
+}
+
+// The broadcast may have failed after the cluster accepted the transaction, so
+// the row is only settled when the chain has a definite answer
+async fn settle_after_error(
+    state: &AppState,
+    record: &TransactionRecord,
+    err: AppError,
+) -> Result<TransactionResponse> {
+    // Rejected in preflight, so it never reached the cluster
+    if let AppError::TransactionFailed(reason) = &err {
+        state
+            .database
+            .record_transaction_outcome(
+                &record.transaction_hash,
+                "failed",
+                None,
+                serde_json::json!({ "failure_reason": "preflight_failed", "error": reason }),
+            )
+            .await?;
+        return Err(err);
+    }
+
+    let status = match state
+        .blockchain
+        .get_transaction_status(&record.transaction_hash)
+        .await
+    {
+        Ok(status) => status,
+        Err(_) => return Err(err),
+    };
+
+    match status.status.as_str() {
+        "confirmed" => {
+            state
+                .database
+                .update_transaction_status(&status.signature, "confirmed", status.slot)
+                .await?;
+            Ok(TransactionResponse {
+                signature: status.signature,
+                slot: status.slot,
+                status: status.status,
+            })
+        }
+        "failed" => {
+            state
+                .database
+                .update_transaction_status(&status.signature, "failed", status.slot)
+                .await?;
+            Err(err)
+        }
+        _ => Err(err),
+    }
+}
+
+fn prepared_response(
+    state: &AppState,
+    built: &BuiltTransaction,
+) -> Result<PreparedTransactionResponse> {
+    let transaction = &built.transaction;
+
+    Ok(PreparedTransactionResponse {
+        transaction: BlockchainService::encode_transaction(transaction)?,
+        signature: built.signature(),
+        signers: state.blockchain.pending_signers(transaction),
+        recent_blockhash: transaction.message.recent_blockhash.to_string(),
+        last_valid_block_height: built.last_valid_block_height,
+    })
+}
+
+// Mint tokens to an address
+async fn mint_tokens(
+    State(state): State<AppState>,
+    user: AuthUser,
+    headers: HeaderMap,
+    Json(payload): Json<MintTokensRequest>,
+) -> Result<Json<ApiResponse<TransactionResponse>>> {
+    user.authorize(payload.user_id)?;
+
+    let mint_pubkey = Pubkey::from_str(&payload.mint_address)
+        .map_err(|_| AppError::InvalidInput("Invalid mint address".to_string()))?;
+    
+    let destination_pubkey = Pubkey::from_str(&payload.destination_address)
+        .map_err(|_| AppError::InvalidInput("Invalid destination address".to_string()))?;
+
+    let amount = token_amount(&state, &mint_pubkey, payload.amount, payload.ui_amount.as_deref()).await?;
+
+    let key = idempotency_key(&headers, payload.idempotency_key.as_deref());
+    let result = run_once(&state, &user, "mint_tokens", key, &payload, || async {
+        let built = state.blockchain.mint_tokens(
+            &mint_pubkey,
+            &destination_pubkey,
+            amount,
+            &payload.authority,
+            &payload.fees,
+        ).await?;
+
+        let transaction_record = mint_transaction_record(&payload, amount, &built);
+
+        broadcast_recorded(&state, built, &transaction_record).await
+    }).await?;
+
+    Ok(Json(ApiResponse::success(result)))
+}
+
+// Build a mint transaction for the mint authority's wallet to sign
+async fn prepare_mint_tokens(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(payload): Json<MintTokensRequest>,
+) -> Result<Json<ApiResponse<PreparedTransactionResponse>>> {
+    user.authorize(payload.user_id)?;
+
+    let mint_pubkey = Pubkey::from_str(&payload.mint_address)
+        .map_err(|_| AppError::InvalidInput("Invalid mint address".to_string()))?;
+    
+    let destination_pubkey = Pubkey::from_str(&payload.destination_address)
+        .map_err(|_| AppError::InvalidInput("Invalid destination address".to_string()))?;
+
+    let amount = token_amount(&state, &mint_pubkey, payload.amount, payload.ui_amount.as_deref()).await?;
+
+    let built = state.blockchain.prepare_mint_tokens(
+        &mint_pubkey,
+        &destination_pubkey,
+        amount,
+        &payload.authority,
+        &payload.fees,
+    ).await?;
+
+    let response = prepared_response(&state, &built)?;
+    let transaction_record = mint_transaction_record(&payload, amount, &built);
+
+    state.prepared.insert(&built, transaction_record).await?;
+
+    Ok(Json(ApiResponse::success(response)))
+}
+
+// Transfer tokens between addresses
+async fn transfer_tokens(
+    State(state): State<AppState>,
+    user: AuthUser,
+    headers: HeaderMap,
+    Json(payload): Json<TransferTokensRequest>,
+) -> Result<Json<ApiResponse<TransactionResponse>>> {
+    user.authorize(payload.user_id)?;
+
+    let mint_pubkey = Pubkey::from_str(&payload.mint_address)
+        .map_err(|_| AppError::InvalidInput("Invalid mint address".to_string()))?;
+    
+    let from_pubkey = Pubkey::from_str(&payload.from_address)
+        .map_err(|_| AppError::InvalidInput("Invalid from address".to_string()))?;
+    
+    let to_pubkey = Pubkey::from_str(&payload.to_address)
+        .map_err(|_| AppError::InvalidInput("Invalid to address".to_string()))?;
+
+    let amount = token_amount(&state, &mint_pubkey, payload.amount, payload.ui_amount.as_deref()).await?;
+
+    let key = idempotency_key(&headers, payload.idempotency_key.as_deref());
+    let result = run_once(&state, &user, "transfer_tokens", key, &payload, || async {
+        let built = state.blockchain.transfer_tokens(
+            &mint_pubkey,
+            &from_pubkey,
+            &to_pubkey,
+            amount,
+            &payload.owner,
+            &payload.fees,
+        ).await?;
+
+        let transaction_record = transfer_transaction_record(&payload, amount, &built);
+
+        broadcast_recorded(&state, built, &transaction_record).await
+    }).await?;
+
+    Ok(Json(ApiResponse::success(result)))
+}
+
+// Build a transfer transaction for the owner's wallet to sign
+async fn prepare_transfer_tokens(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(payload): Json<TransferTokensRequest>,
+) -> Result<Json<ApiResponse<PreparedTransactionResponse>>> {
+    user.authorize(payload.user_id)?;
+
+    let mint_pubkey = Pubkey::from_str(&payload.mint_address)
+        .map_err(|_| AppError::InvalidInput("Invalid mint address".to_string()))?;
+    
+    let from_pubkey = Pubkey::from_str(&payload.from_address)
+        .map_err(|_| AppError::InvalidInput("Invalid from address".to_string()))?;
+    
+    let to_pubkey = Pubkey::from_str(&payload.to_address)
+        .map_err(|_| AppError::InvalidInput("Invalid to address".to_string()))?;
+
+    let amount = token_amount(&state, &mint_pubkey, payload.amount, payload.ui_amount.as_deref()).await?;
+
+    let built = state.blockchain.prepare_transfer_tokens(
+        &mint_pubkey,
+        &from_pubkey,
+        &to_pubkey,
+        amount,
+        &payload.owner,
+        &payload.fees,
+    ).await?;
+
+    let response = prepared_response(&state, &built)?;
+    let transaction_record = transfer_transaction_record(&payload, amount, &built);
+
+    state.prepared.insert(&built, transaction_record).await?;
+
+    Ok(Json(ApiResponse::success(response)))
+}
+
+// Burn tokens held by an owner's associated token account
+async fn burn_tokens(
+    State(state): State<AppState>,
+    user: AuthUser,
+    headers: HeaderMap,
+    Json(payload): Json<BurnTokensRequest>,
+) -> Result<Json<ApiResponse<TransactionResponse>>> {
+    user.authorize(payload.user_id)?;
+
+    let mint_pubkey = Pubkey::from_str(&payload.mint_address)
+        .map_err(|_| AppError::InvalidInput("Invalid mint address".to_string()))?;
+    
+    let owner_pubkey = Pubkey::from_str(&payload.owner_address)
+        .map_err(|_| AppError::InvalidInput("Invalid owner address".to_string()))?;
+
+    let amount = token_amount(&state, &mint_pubkey, payload.amount, payload.ui_amount.as_deref()).await?;
+
+    let key = idempotency_key(&headers, payload.idempotency_key.as_deref());
+    let result = run_once(&state, &user, "burn_tokens", key, &payload, || async {
+        let built = state.blockchain.burn_tokens(
+            &mint_pubkey,
+            &owner_pubkey,
+            amount,
+            &payload.authority,
+            &payload.fees,
+        ).await?;
+
+        let transaction_record = burn_transaction_record(&payload, amount, &built);
+
+        broadcast_recorded(&state, built, &transaction_record).await
+    }).await?;
+
+    Ok(Json(ApiResponse::success(result)))
+}
+
+// Build a burn transaction for the owner's wallet to sign
+async fn prepare_burn_tokens(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(payload): Json<BurnTokensRequest>,
+) -> Result<Json<ApiResponse<PreparedTransactionResponse>>> {
+    user.authorize(payload.user_id)?;
+
+    let mint_pubkey = Pubkey::from_str(&payload.mint_address)
+        .map_err(|_| AppError::InvalidInput("Invalid mint address".to_string()))?;
+    
+    let owner_pubkey = Pubkey::from_str(&payload.owner_address)
+        .map_err(|_| AppError::InvalidInput("Invalid owner address".to_string()))?;
+
+    let amount = token_amount(&state, &mint_pubkey, payload.amount, payload.ui_amount.as_deref()).await?;
+
+    let built = state.blockchain.prepare_burn_tokens(
+        &mint_pubkey,
+        &owner_pubkey,
+        amount,
+        &payload.authority,
+        &payload.fees,
+    ).await?;
+
+    let response = prepared_response(&state, &built)?;
+    let transaction_record = burn_transaction_record(&payload, amount, &built);
+
+    state.prepared.insert(&built, transaction_record).await?;
+
+    Ok(Json(ApiResponse::success(response)))
+}
+
+// Issue a soulbound membership badge to a user's primary wallet. Only admins
+// may grant memberships, and each user holds at most one badge per mint.
+async fn grant_membership(
+    State(state): State<AppState>,
+    user: AuthUser,
+    headers: HeaderMap,
+    Json(payload): Json<GrantMembershipRequest>,
+) -> Result<Json<ApiResponse<MembershipResponse>>> {
+    if !user.is_admin() {
+        return Err(AppError::Forbidden("Only admins can grant memberships".to_string()));
+    }
+
+    let mint_pubkey = Pubkey::from_str(&payload.mint_address)
+        .map_err(|_| AppError::InvalidInput("Invalid mint address".to_string()))?;
+
+    let mint = state
+        .database
+        .get_mint_info(&payload.mint_address)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Mint not found".to_string()))?;
+
+    let mint_authority = mint
+        .mint_authority
+        .ok_or_else(|| AppError::InvalidInput("Mint authority has been revoked".to_string()))?;
+
+    if !state.blockchain.is_non_transferable(&mint_pubkey).await? {
+        return Err(AppError::InvalidInput(
+            "Mint is not a non-transferable membership mint".to_string(),
+        ));
+    }
+
+    let wallet_address = state
+        .database
+        .get_primary_wallet(payload.user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User has no primary wallet".to_string()))?;
+
+    let wallet_pubkey = Pubkey::from_str(&wallet_address)
+        .map_err(|_| AppError::InvalidInput("User's primary wallet address is invalid".to_string()))?;
+
+    // One whole badge, whatever the mint's decimals
+    let amount = TokenAmount::from_ui_str("1", mint.decimals as u8)?;
+
+    let key = idempotency_key(&headers, payload.idempotency_key.as_deref());
+    let result = run_once(&state, &user, "grant_membership", key, &payload, || async {
+        // A badge that reached the wallet some other way counts as well
+        let held = state
+            .blockchain
+            .get_balances(&[wallet_pubkey], &[mint_pubkey])
+            .await?
+            .into_iter()
+            .flat_map(|(_, tokens)| tokens)
+            .any(|token| token.balance.raw() > 0);
+        if held {
+            return Err(AppError::Conflict("User already holds this membership".to_string()));
+        }
+
+        if !state
+            .database
+            .claim_membership(payload.user_id, &payload.mint_address, &wallet_address)
+            .await?
+        {
+            return Err(AppError::Conflict("User already holds this membership".to_string()));
+        }
+
+        let minted = async {
+            let built = state.blockchain.mint_tokens(
+                &mint_pubkey,
+                &wallet_pubkey,
+                amount,
+                &mint_authority,
+                &payload.fees,
+            ).await?;
+
+            let transaction_record = membership_transaction_record(&payload, &wallet_address, amount, &built);
+
+            broadcast_recorded(&state, built, &transaction_record).await
+        }.await;
+
+        match minted {
+            Ok(transaction) => {
+                state
+                    .database
+                    .complete_membership(payload.user_id, &payload.mint_address, &transaction.signature)
+                    .await?;
+
+                Ok(MembershipResponse {
+                    user_id: payload.user_id,
+                    mint_address: payload.mint_address.clone(),
+                    wallet_address: wallet_address.clone(),
+                    transaction,
+                })
+            }
+            Err(err) => {
+                if err.had_no_effect() {
+                    state
+                        .database
+                        .release_membership(payload.user_id, &payload.mint_address)
+                        .await?;
+                }
+                Err(err)
+            }
+        }
+    }).await?;
+
+    Ok(Json(ApiResponse::success(result)))
+}
+
+// Longest prefix of `value` that fits in `max` bytes
+fn truncate_bytes(value: &str, max: usize) -> &str {
+    let mut end = value.len().min(max);
+    while !value.is_char_boundary(end) {
+        end -= 1;
+    }
+    &value[..end]
+}
+
+// Stored row for a one-of-one NFT mint created by the service
+fn nft_mint_info(
+    mint: &Pubkey,
+    built: &BuiltTransaction,
+    metadata: &TokenMetadata,
+    mint_authority: Option<String>,
+    freeze_authority: Option<String>,
+) -> MintResponse {
+    MintResponse {
+        mint_address: mint.to_string(),
+        signature: built.signature(),
+        decimals: 0,
+        mint_authority,
+        freeze_authority,
+        token_program: TokenProgram::SplToken,
+        extensions: MintExtensions::default(),
+        metadata: Some(metadata.clone()),
+    }
+}
+
+// Sends the transaction that creates a mint once the mint's row is stored,
+// so the supply update on confirmation finds it; the row is deactivated
+// again if the transaction definitely did not land
+async fn broadcast_new_mint(
+    state: &AppState,
+    mint: &MintResponse,
+    built: BuiltTransaction,
+    record: &TransactionRecord,
+) -> Result<TransactionResponse> {
+    state.database.store_mint_info(mint).await?;
+
+    match broadcast_recorded(state, built, record).await {
+        Ok(transaction) => Ok(transaction),
+        Err(err) => {
+            if err.had_no_effect() {
+                state.database.deactivate_mint(&mint.mint_address).await?;
+            }
+            Err(err)
+        }
+    }
+}
+
+// Issue the NFT ticket for an event registration to the attendee's primary
+// wallet. Attendees may request their own ticket; admins may issue any.
+async fn issue_ticket(
+    State(state): State<AppState>,
+    user: AuthUser,
+    headers: HeaderMap,
+    Json(payload): Json<IssueTicketRequest>,
+) -> Result<Json<ApiResponse<TicketResponse>>> {
+    let registration = state
+        .database
+        .get_event_registration(payload.registration_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Registration not found".to_string()))?;
+
+    user.authorize(registration.user_id)?;
+
+    let wallet_address = state
+        .database
+        .get_primary_wallet(registration.user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User has no primary wallet".to_string()))?;
+
+    let wallet_pubkey = Pubkey::from_str(&wallet_address)
+        .map_err(|_| AppError::InvalidInput("User's primary wallet address is invalid".to_string()))?;
+
+    let metadata = TokenMetadata {
+        name: truncate_bytes(&format!("{} Ticket", registration.event_title), 32).to_string(),
+        symbol: "TICKET".to_string(),
+        uri: payload.uri.clone().unwrap_or_default(),
+    };
+
+    let key = idempotency_key(&headers, payload.idempotency_key.as_deref());
+    let result = run_once(&state, &user, "issue_ticket", key, &payload, || async {
+        let ticket_id = state
+            .database
+            .claim_ticket(&registration, &wallet_address)
+            .await?
+            .ok_or_else(|| {
+                AppError::Conflict("A ticket was already issued for this registration".to_string())
+            })?;
+
+        let issued = async {
+            let (built, mint) = state
+                .blockchain
+                .build_ticket(&wallet_pubkey, &metadata, &payload.fees)
+                .await?;
+
+            state.database.set_ticket_mint(ticket_id, &mint.to_string()).await?;
+
+            let mint_info = nft_mint_info(
+                &mint,
+                &built,
+                &metadata,
+                None,
+                Some(state.blockchain.payer_pubkey().to_string()),
+            );
+            let transaction_record = ticket_transaction_record(&registration, &wallet_address, &mint, &built);
+
+            let transaction = broadcast_new_mint(&state, &mint_info, built, &transaction_record).await?;
+            Ok((mint, transaction))
+        }.await;
+
+        match issued {
+            Ok((mint, transaction)) => {
+                state.database.complete_ticket_issue(ticket_id, &transaction.signature).await?;
+
+                Ok(TicketResponse {
+                    ticket_id,
+                    registration_id: registration.id,
+                    event_id: registration.event_id,
+                    user_id: registration.user_id,
+                    mint_address: mint.to_string(),
+                    wallet_address: wallet_address.clone(),
+                    status: "issued".to_string(),
+                    transaction,
+                })
+            }
+            Err(err) => {
+                if err.had_no_effect() {
+                    state.database.release_ticket(ticket_id).await?;
+                }
+                Err(err)
+            }
+        }
+    }).await?;
+
+    Ok(Json(ApiResponse::success(result)))
+}
+
+// Collections are created and extended by admins; the service payer is the
+// collection's update authority, which is what verifies items into it
+async fn create_collection(
+    State(state): State<AppState>,
+    user: AuthUser,
+    headers: HeaderMap,
+    Json(payload): Json<CreateCollectionRequest>,
+) -> Result<Json<ApiResponse<CollectionResponse>>> {
+    if !user.is_admin() {
+        return Err(AppError::Forbidden("Only admins can create collections".to_string()));
+    }
+
+    let metadata = TokenMetadata {
+        name: payload.name.clone(),
+        symbol: payload.symbol.clone(),
+        uri: payload.uri.clone(),
+    };
+
+    let key = idempotency_key(&headers, payload.idempotency_key.as_deref());
+    let result = run_once(&state, &user, "create_collection", key, &payload, || async {
+        let (built, mint) = state.blockchain.build_collection(&metadata, &payload.fees).await?;
+
+        let edition = blockchain::master_edition_address(&mint).to_string();
+        let mint_info = nft_mint_info(&mint, &built, &metadata, Some(edition.clone()), Some(edition));
+        let payer = state.blockchain.payer_pubkey().to_string();
+        let transaction_record = collection_mint_transaction_record(user.user_id, None, &mint, &payer, &built);
+
+        let collection_id = state
+            .database
+            .store_collection(&mint.to_string(), &metadata, user.user_id)
+            .await?;
+
+        match broadcast_new_mint(&state, &mint_info, built, &transaction_record).await {
+            Ok(transaction) => Ok(CollectionResponse {
+                collection_id,
+                mint_address: mint.to_string(),
+                metadata: metadata.clone(),
+                transaction,
+            }),
+            Err(err) => {
+                if err.had_no_effect() {
+                    state.database.delete_collection(collection_id).await?;
+                }
+                Err(err)
+            }
+        }
+    }).await?;
+
+    Ok(Json(ApiResponse::success(result)))
+}
+
+// Mint an NFT into a collection, verified in the same transaction
+async fn mint_collection_item(
+    State(state): State<AppState>,
+    user: AuthUser,
+    headers: HeaderMap,
+    Json(payload): Json<MintCollectionItemRequest>,
+) -> Result<Json<ApiResponse<CollectionItemResponse>>> {
+    if !user.is_admin() {
+        return Err(AppError::Forbidden("Only admins can mint collection items".to_string()));
+    }
+
+    let collection_pubkey = Pubkey::from_str(&payload.collection_address)
+        .map_err(|_| AppError::InvalidInput("Invalid collection address".to_string()))?;
+
+    let collection = state
+        .database
+        .get_collection(&payload.collection_address)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Collection not found".to_string()))?;
+
+    let owner_address = match (&payload.owner_address, payload.user_id) {
+        (Some(owner_address), _) => owner_address.clone(),
+        (None, Some(user_id)) => state
+            .database
+            .get_primary_wallet(user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("User has no primary wallet".to_string()))?,
+        (None, None) => {
+            return Err(AppError::InvalidInput(
+                "Either owner_address or user_id is required".to_string(),
+            ))
+        }
+    };
+
+    let owner_pubkey = Pubkey::from_str(&owner_address)
+        .map_err(|_| AppError::InvalidInput("Invalid owner address".to_string()))?;
+
+    let metadata = TokenMetadata {
+        name: payload.name.clone(),
+        symbol: payload.symbol.clone(),
+        uri: payload.uri.clone(),
+    };
+
+    let key = idempotency_key(&headers, payload.idempotency_key.as_deref());
+    let result = run_once(&state, &user, "mint_collection_item", key, &payload, || async {
+        let (built, mint) = state
+            .blockchain
+            .build_collection_item(&collection_pubkey, &owner_pubkey, &metadata, &payload.fees)
+            .await?;
+
+        let edition = blockchain::master_edition_address(&mint).to_string();
+        let mint_info = nft_mint_info(&mint, &built, &metadata, Some(edition.clone()), Some(edition));
+        let transaction_record = collection_mint_transaction_record(
+            payload.user_id.unwrap_or(user.user_id),
+            Some(&collection_pubkey),
+            &mint,
+            &owner_address,
+            &built,
+        );
+
+        state.database.store_collection_item(
+            collection.id,
+            &mint.to_string(),
+            Some(&owner_address),
+            payload.user_id,
+            &metadata,
+            &built.signature(),
+        ).await?;
+
+        match broadcast_new_mint(&state, &mint_info, built, &transaction_record).await {
+            Ok(transaction) => Ok(CollectionItemResponse {
+                collection_address: payload.collection_address.clone(),
+                mint_address: mint.to_string(),
+                owner_address: owner_address.clone(),
+                user_id: payload.user_id,
+                transaction,
+            }),
+            Err(err) => {
+                if err.had_no_effect() {
+                    state.database.delete_collection_item(&mint.to_string()).await?;
+                }
+                Err(err)
+            }
+        }
+    }).await?;
+
+    Ok(Json(ApiResponse::success(result)))
+}
+
+// Verify an NFT minted elsewhere whose metadata names the collection
+async fn verify_collection_item(
+    State(state): State<AppState>,
+    user: AuthUser,
+    headers: HeaderMap,
+    Json(payload): Json<VerifyCollectionItemRequest>,
+) -> Result<Json<ApiResponse<TransactionResponse>>> {
+    if !user.is_admin() {
+        return Err(AppError::Forbidden("Only admins can verify collection items".to_string()));
+    }
+
+    let collection_pubkey = Pubkey::from_str(&payload.collection_address)
+        .map_err(|_| AppError::InvalidInput("Invalid collection address".to_string()))?;
+
+    let item_pubkey = Pubkey::from_str(&payload.mint_address)
+        .map_err(|_| AppError::InvalidInput("Invalid mint address".to_string()))?;
+
+    let collection = state
+        .database
+        .get_collection(&payload.collection_address)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Collection not found".to_string()))?;
+
+    let key = idempotency_key(&headers, payload.idempotency_key.as_deref());
+    let result = run_once(&state, &user, "verify_collection_item", key, &payload, || async {
+        let (built, metadata) = state
+            .blockchain
+            .verify_collection_item(&collection_pubkey, &item_pubkey, &payload.fees)
+            .await?;
+
+        state.database.store_collection_item(
+            collection.id,
+            &payload.mint_address,
+            None,
+            None,
+            &metadata,
+            &built.signature(),
+        ).await?;
+
+        let transaction_record = verify_collection_transaction_record(&user, &payload, &built);
+
+        match broadcast_recorded(&state, built, &transaction_record).await {
+            Ok(transaction) => Ok(transaction),
+            Err(err) => {
+                if err.had_no_effect() {
+                    state.database.delete_collection_item(&payload.mint_address).await?;
+                }
+                Err(err)
+            }
+        }
+    }).await?;
+
+    Ok(Json(ApiResponse::success(result)))
+}
+
+// Items recorded for a collection, in minting order
+async fn get_collection_items(
+    Path(address): Path<String>,
+    Query(params): Query<CollectionItemsQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<Vec<CollectionItem>>>> {
+    let collection = state
+        .database
+        .get_collection(&address)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Collection not found".to_string()))?;
+
+    let items = state.database.get_collection_items(
+        collection.id,
+        params.limit.unwrap_or(50),
+        params.offset.unwrap_or(0),
+    ).await?;
+
+    Ok(Json(ApiResponse::success(items)))
+}
+
+// Whether a wallet holds a verified item of the collection, checked on
+// chain so the answer does not depend on how the item got there
+async fn get_collection_ownership(
+    Path(address): Path<String>,
+    Query(params): Query<CollectionOwnershipQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<CollectionOwnershipResponse>>> {
+    let collection_pubkey = Pubkey::from_str(&address)
+        .map_err(|_| AppError::InvalidInput("Invalid collection address".to_string()))?;
+
+    let item_pubkey = Pubkey::from_str(&params.item)
+        .map_err(|_| AppError::InvalidInput("Invalid item address".to_string()))?;
+
+    let wallet_pubkey = Pubkey::from_str(&params.wallet)
+        .map_err(|_| AppError::InvalidInput("Invalid wallet address".to_string()))?;
+
+    let (in_collection, verified) = state
+        .blockchain
+        .get_collection_membership(&collection_pubkey, &item_pubkey)
+        .await?;
+
+    let holds_item = state
+        .blockchain
+        .get_balances(&[wallet_pubkey], &[item_pubkey])
+        .await?
+        .into_iter()
+        .flat_map(|(_, tokens)| tokens)
+        .any(|token| token.balance.raw() == 1);
+
+    Ok(Json(ApiResponse::success(CollectionOwnershipResponse {
+        collection_address: address,
+        mint_address: params.item,
+        wallet_address: params.wallet,
+        in_collection,
+        verified,
+        holds_item,
+        owns_collection_item: in_collection && verified && holds_item,
+    })))
+}
+
+// Recipients accepted in one airdrop job
+const MAX_AIRDROP_RECIPIENTS: usize = 10_000;
+
+// Queue a batch mint to many recipients; it runs in the background and its
+// progress is polled through the status and report endpoints
+async fn create_airdrop(
+    State(state): State<AppState>,
+    user: AuthUser,
+    headers: HeaderMap,
+    Json(payload): Json<CreateAirdropRequest>,
+) -> Result<Json<ApiResponse<AirdropJobStatus>>> {
+    if !user.is_admin() {
+        return Err(AppError::Forbidden("Only admins can create airdrops".to_string()));
+    }
+
+    let mint_pubkey = Pubkey::from_str(&payload.mint_address)
+        .map_err(|_| AppError::InvalidInput("Invalid mint address".to_string()))?;
+
+    if payload.recipients.is_empty() || payload.recipients.len() > MAX_AIRDROP_RECIPIENTS {
+        return Err(AppError::InvalidInput(format!(
+            "An airdrop needs between 1 and {} recipients",
+            MAX_AIRDROP_RECIPIENTS
+        )));
+    }
+
+    // Every batch is signed by the payer as mint authority, so a job that
+    // could never mint is rejected up front
+    let (mint, _) = state.blockchain.get_mint_account(&mint_pubkey).await?;
+    if Option::<Pubkey>::from(mint.mint_authority) != Some(state.blockchain.payer_pubkey()) {
+        return Err(AppError::InvalidInput(
+            "The service payer is not the mint authority of this mint".to_string(),
+        ));
+    }
+
+    let mut recipients = Vec::with_capacity(payload.recipients.len());
+    let mut total: u64 = 0;
+    for (position, recipient) in payload.recipients.iter().enumerate() {
+        let destination = Pubkey::from_str(&recipient.destination).map_err(|_| {
+            AppError::InvalidInput(format!("Invalid destination address at position {}", position))
+        })?;
+        let amount = parse_token_amount(mint.decimals, recipient.amount, recipient.ui_amount.as_deref())
+            .map_err(|err| match err {
+                AppError::InvalidInput(message) => {
+                    AppError::InvalidInput(format!("Recipient at position {}: {}", position, message))
+                }
+                other => other,
+            })?;
+
+        total = total
+            .checked_add(amount.raw())
+            .ok_or_else(|| AppError::InvalidInput("Total airdrop amount is too large".to_string()))?;
+        recipients.push((destination.to_string(), amount));
+    }
+    let total_amount = TokenAmount::new(total, mint.decimals);
+
+    let key = idempotency_key(&headers, payload.idempotency_key.as_deref());
+    let result = run_once(&state, &user, "create_airdrop", key, &payload, || async {
+        let job_id = state
+            .database
+            .create_airdrop_job(&payload.mint_address, user.user_id, &payload.fees, &recipients, total_amount)
+            .await?;
+
+        airdrop::start(&state, job_id);
+
+        state
+            .database
+            .get_airdrop_job_status(job_id)
+            .await?
+            .ok_or_else(|| AppError::Internal("Airdrop job vanished after creation".to_string()))
+    }).await?;
+
+    Ok(Json(ApiResponse::success(result)))
+}
+
+async fn get_airdrop(
+    Path(job_id): Path<Uuid>,
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> Result<Json<ApiResponse<AirdropJobStatus>>> {
+    if !user.is_admin() {
+        return Err(AppError::Forbidden("Only admins can view airdrops".to_string()));
+    }
+
+    let status = state
+        .database
+        .get_airdrop_job_status(job_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Airdrop job not found".to_string()))?;
+
+    Ok(Json(ApiResponse::success(status)))
+}
+
+// Per-recipient results of an airdrop, in submission order
+async fn get_airdrop_recipients(
+    Path(job_id): Path<Uuid>,
+    Query(params): Query<AirdropRecipientsQuery>,
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> Result<Json<ApiResponse<Vec<AirdropRecipientRecord>>>> {
+    if !user.is_admin() {
+        return Err(AppError::Forbidden("Only admins can view airdrops".to_string()));
+    }
+
+    let recipients = state.database.get_airdrop_recipients(
+        job_id,
+        params.status.as_deref(),
+        params.limit.unwrap_or(100),
+        params.offset.unwrap_or(0),
+    ).await?;
+
+    Ok(Json(ApiResponse::success(recipients)))
+}
+
+// Entries accepted in one claimable airdrop campaign
+const MAX_CAMPAIGN_ENTRIES: usize = 100_000;
+
+// Create a claimable airdrop: the Merkle root and every entry's proof are
+// stored, and nothing is paid out until a wallet claims
+async fn create_campaign(
+    State(state): State<AppState>,
+    user: AuthUser,
+    headers: HeaderMap,
+    Json(payload): Json<CreateCampaignRequest>,
+) -> Result<Json<ApiResponse<CampaignRecord>>> {
+    if !user.is_admin() {
+        return Err(AppError::Forbidden("Only admins can create airdrop campaigns".to_string()));
+    }
+
+    let mint_pubkey = Pubkey::from_str(&payload.mint_address)
+        .map_err(|_| AppError::InvalidInput("Invalid mint address".to_string()))?;
+
+    if payload.entries.is_empty() || payload.entries.len() > MAX_CAMPAIGN_ENTRIES {
+        return Err(AppError::InvalidInput(format!(
+            "A campaign needs between 1 and {} entries",
+            MAX_CAMPAIGN_ENTRIES
+        )));
+    }
+
+    let (mint, _) = state.blockchain.get_mint_account(&mint_pubkey).await?;
+    let payer = state.blockchain.payer_pubkey();
+    if payload.distribution == CampaignDistribution::Mint
+        && Option::<Pubkey>::from(mint.mint_authority) != Some(payer)
+    {
+        return Err(AppError::InvalidInput(
+            "The service payer is not the mint authority of this mint".to_string(),
+        ));
+    }
+
+    let mut wallets = HashSet::new();
+    let mut leaves = Vec::with_capacity(payload.entries.len());
+    let mut amounts = Vec::with_capacity(payload.entries.len());
+    let mut total: u64 = 0;
+    for (position, entry) in payload.entries.iter().enumerate() {
+        let wallet = Pubkey::from_str(&entry.wallet_address).map_err(|_| {
+            AppError::InvalidInput(format!("Invalid wallet address at position {}", position))
+        })?;
+        // One leaf per wallet, so a claim is identified by its wallet alone
+        if !wallets.insert(wallet) {
+            return Err(AppError::InvalidInput(format!("Wallet {} is listed more than once", wallet)));
+        }
+
+        let amount = parse_token_amount(mint.decimals, entry.amount, entry.ui_amount.as_deref())
+            .map_err(|err| match err {
+                AppError::InvalidInput(message) => {
+                    AppError::InvalidInput(format!("Entry at position {}: {}", position, message))
+                }
+                other => other,
+            })?;
+
+        total = total
+            .checked_add(amount.raw())
+            .ok_or_else(|| AppError::InvalidInput("Total campaign amount is too large".to_string()))?;
+        leaves.push(merkle::leaf_hash(&wallet, amount.raw()));
+        amounts.push((wallet, amount));
+    }
+    let total_amount = TokenAmount::new(total, mint.decimals);
+
+    if payload.distribution == CampaignDistribution::Transfer {
+        let held = state
+            .blockchain
+            .get_balances(&[payer], &[mint_pubkey])
+            .await?
+            .into_iter()
+            .flat_map(|(_, tokens)| tokens)
+            .map(|token| token.balance.raw())
+            .sum::<u64>();
+        if held < total {
+            return Err(AppError::InvalidInput(format!(
+                "The service payer holds {} of the {} this campaign distributes",
+                TokenAmount::new(held, mint.decimals),
+                total_amount
+            )));
+        }
+    }
+
+    let tree = merkle::MerkleTree::new(leaves);
+    let entries: Vec<(String, TokenAmount, Vec<String>)> = amounts
+        .into_iter()
+        .enumerate()
+        .map(|(index, (wallet, amount))| {
+            let proof = tree.proof(index).iter().map(Hash::to_string).collect();
+            (wallet.to_string(), amount, proof)
+        })
+        .collect();
+
+    let key = idempotency_key(&headers, payload.idempotency_key.as_deref());
+    let result = run_once(&state, &user, "create_campaign", key, &payload, || async {
+        state
+            .database
+            .create_campaign(&payload, &tree.root().to_string(), user.user_id, &entries, total_amount)
+            .await
+    }).await?;
+
+    Ok(Json(ApiResponse::success(result)))
+}
+
+// Campaign details are visible to any signed-in user, so they can check
+// whether a campaign is open before asking for their proof
+async fn get_campaign(
+    Path(campaign_id): Path<Uuid>,
+    State(state): State<AppState>,
+    _user: AuthUser,
+) -> Result<Json<ApiResponse<CampaignRecord>>> {
+    let campaign = state
+        .database
+        .get_campaign(campaign_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Campaign not found".to_string()))?;
+
+    Ok(Json(ApiResponse::success(campaign)))
+}
+
+fn parse_proof(proof: &serde_json::Value) -> Result<Vec<Hash>> {
+    let invalid = || AppError::Internal("Stored Merkle proof is malformed".to_string());
+
+    proof
+        .as_array()
+        .ok_or_else(invalid)?
+        .iter()
+        .map(|node| node.as_str().and_then(|node| Hash::from_str(node).ok()).ok_or_else(invalid))
+        .collect()
+}
+
+// A wallet's allocation in a campaign with its Merkle proof, shown only to
+// the wallet's owner or an admin
+async fn get_campaign_proof(
+    Path(campaign_id): Path<Uuid>,
+    Query(params): Query<CampaignProofQuery>,
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> Result<Json<ApiResponse<CampaignProofResponse>>> {
+    if !user.is_admin() && !state.database.user_has_wallet(user.user_id, &params.wallet).await? {
+        return Err(AppError::Forbidden(
+            "Wallet does not belong to the authenticated user".to_string(),
+        ));
+    }
+
+    let campaign = state
+        .database
+        .get_campaign(campaign_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Campaign not found".to_string()))?;
+
+    let entry = state
+        .database
+        .get_campaign_entry(campaign_id, &params.wallet)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Wallet has no allocation in this campaign".to_string()))?;
+
+    let proof = parse_proof(&entry.proof)?;
+
+    Ok(Json(ApiResponse::success(CampaignProofResponse {
+        campaign_id,
+        merkle_root: campaign.merkle_root,
+        wallet_address: entry.wallet_address,
+        index: entry.position,
+        amount: entry.amount,
+        proof: proof.iter().map(Hash::to_string).collect(),
+        status: entry.status,
+    })))
+}
+
+// Pay out a wallet's allocation once the proof it presents checks out against
+// the campaign root; the claimed flag makes each allocation payable once
+async fn claim_campaign(
+    State(state): State<AppState>,
+    user: AuthUser,
+    headers: HeaderMap,
+    Json(payload): Json<ClaimCampaignRequest>,
+) -> Result<Json<ApiResponse<CampaignClaimResponse>>> {
+    let wallet_pubkey = Pubkey::from_str(&payload.wallet_address)
+        .map_err(|_| AppError::InvalidInput("Invalid wallet address".to_string()))?;
+
+    if !user.is_admin() && !state.database.user_has_wallet(user.user_id, &payload.wallet_address).await? {
+        return Err(AppError::Forbidden(
+            "Wallet does not belong to the authenticated user".to_string(),
+        ));
+    }
+
+    let campaign = state
+        .database
+        .get_campaign(payload.campaign_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Campaign not found".to_string()))?;
+
+    if !campaign.is_active {
+        return Err(AppError::Conflict("Campaign is closed".to_string()));
+    }
+
+    let entry = state
+        .database
+        .get_campaign_entry(payload.campaign_id, &payload.wallet_address)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Wallet has no allocation in this campaign".to_string()))?;
+
+    // The caller's proof must tie the stored amount to the root fixed at
+    // creation, so an allocation edited afterwards cannot be paid out
+    let root = Hash::from_str(&campaign.merkle_root)
+        .map_err(|_| AppError::Internal("Stored Merkle root is malformed".to_string()))?;
+    let proof = payload
+        .proof
+        .iter()
+        .map(|node| {
+            Hash::from_str(node).map_err(|_| AppError::InvalidInput("Invalid Merkle proof".to_string()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let leaf = merkle::leaf_hash(&wallet_pubkey, entry.amount.raw());
+    if !merkle::verify(&proof, &root, leaf) {
+        return Err(AppError::Forbidden(
+            "Merkle proof does not match the campaign root".to_string(),
+        ));
+    }
+
+    let mint_pubkey = Pubkey::from_str(&campaign.mint_address)
+        .map_err(|_| AppError::Internal("Stored campaign mint is invalid".to_string()))?;
+
+    let key = idempotency_key(&headers, payload.idempotency_key.as_deref());
+    let result = run_once(&state, &user, "claim_campaign", key, &payload, || async {
+        if !state.database.begin_campaign_claim(entry.id).await? {
+            return Err(AppError::Conflict("Allocation was already claimed".to_string()));
+        }
+
+        let claimed = async {
+            let payer = state.blockchain.payer_pubkey();
+            let built = if campaign.distribution == CampaignDistribution::Transfer.as_str() {
+                state
+                    .blockchain
+                    .transfer_tokens(&mint_pubkey, &payer, &wallet_pubkey, entry.amount, &payer.to_string(), &payload.fees)
+                    .await?
+            } else {
+                state
+                    .blockchain
+                    .mint_tokens(&mint_pubkey, &wallet_pubkey, entry.amount, &payer.to_string(), &payload.fees)
+                    .await?
+            };
+
+            let transaction_record = campaign_claim_transaction_record(&user, &campaign, &entry, &payer, &built);
+
+            broadcast_recorded(&state, built, &transaction_record).await
+        }.await;
+
+        match claimed {
+            Ok(transaction) => {
+                state.database.complete_campaign_claim(entry.id, &transaction.signature).await?;
+
+                Ok(CampaignClaimResponse {
+                    campaign_id: campaign.id,
+                    wallet_address: entry.wallet_address.clone(),
+                    amount: entry.amount,
+                    transaction,
+                })
+            }
+            Err(err) => {
+                if err.had_no_effect() {
+                    state.database.release_campaign_claim(entry.id).await?;
+                }
+                Err(err)
+            }
+        }
+    }).await?;
+
+    Ok(Json(ApiResponse::success(result)))
+}
+
+// Check a ticket in at the door: the presented wallet must hold the ticket,
+// which is then frozen in place so it cannot be used again
+async fn check_in_ticket(
+    State(state): State<AppState>,
+    user: AuthUser,
+    headers: HeaderMap,
+    Json(payload): Json<CheckInTicketRequest>,
+) -> Result<Json<ApiResponse<TicketResponse>>> {
+    if !user.is_admin() {
+        return Err(AppError::Forbidden("Only admins can check in tickets".to_string()));
+    }
+
+    let mint_pubkey = Pubkey::from_str(&payload.mint_address)
+        .map_err(|_| AppError::InvalidInput("Invalid mint address".to_string()))?;
+
+    let wallet_pubkey = Pubkey::from_str(&payload.wallet_address)
         .map_err(|_| AppError::InvalidInput("Invalid wallet address".to_string()))?;
 
-    let balance = state.blockchain.get_balance(&pubkey).await?;
+    let ticket = state
+        .database
+        .get_ticket_by_mint(&payload.mint_address)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Ticket not found".to_string()))?;
+
+    let key = idempotency_key(&headers, payload.idempotency_key.as_deref());
+    let result = run_once(&state, &user, "check_in_ticket", key, &payload, || async {
+        let held = state
+            .blockchain
+            .get_balances(&[wallet_pubkey], &[mint_pubkey])
+            .await?
+            .into_iter()
+            .flat_map(|(_, tokens)| tokens)
+            .any(|token| token.balance.raw() == 1);
+        if !held {
+            return Err(AppError::Forbidden("Wallet does not hold this ticket".to_string()));
+        }
+
+        if !state
+            .database
+            .begin_ticket_redemption(ticket.id, &payload.wallet_address)
+            .await?
+        {
+            return Err(AppError::Conflict(
+                "Ticket was already checked in or is not yet issued".to_string(),
+            ));
+        }
+
+        let freeze = FreezeAccountRequest {
+            mint_address: payload.mint_address.clone(),
+            owner_address: payload.wallet_address.clone(),
+            reason: Some("ticket_check_in".to_string()),
+            fees: payload.fees.clone(),
+            idempotency_key: None,
+        };
+        let freeze_authority = state.blockchain.payer_pubkey().to_string();
+
+        let redeemed = async {
+            let built = state.blockchain.set_account_frozen(
+                FreezeAction::Freeze,
+                &mint_pubkey,
+                &wallet_pubkey,
+                &freeze_authority,
+                &payload.fees,
+            ).await?;
+
+            let transaction_record =
+                freeze_transaction_record(&user, FreezeAction::Freeze, &freeze, &freeze_authority, &built);
+
+            broadcast_recorded(&state, built, &transaction_record).await
+        }.await;
+
+        match redeemed {
+            Ok(transaction) => {
+                state
+                    .database
+                    .complete_ticket_redemption(ticket.id, &transaction.signature)
+                    .await?;
+
+                Ok(TicketResponse {
+                    ticket_id: ticket.id,
+                    registration_id: ticket.registration_id,
+                    event_id: ticket.event_id,
+                    user_id: ticket.user_id,
+                    mint_address: payload.mint_address.clone(),
+                    wallet_address: payload.wallet_address.clone(),
+                    status: "redeemed".to_string(),
+                    transaction,
+                })
+            }
+            Err(err) => {
+                if err.had_no_effect() {
+                    state.database.cancel_ticket_redemption(ticket.id).await?;
+                }
+                Err(err)
+            }
+        }
+    }).await?;
+
+    Ok(Json(ApiResponse::success(result)))
+}
+
+// Freezing is a moderation action, so only admins may use it, and only for
+// mints this service created with a freeze authority
+async fn freeze_authority_for(
+    state: &AppState,
+    user: &AuthUser,
+    payload: &FreezeAccountRequest,
+) -> Result<(Pubkey, Pubkey, String)> {
+    if !user.is_admin() {
+        return Err(AppError::Forbidden("Only admins can freeze or thaw accounts".to_string()));
+    }
+
+    let mint_pubkey = Pubkey::from_str(&payload.mint_address)
+        .map_err(|_| AppError::InvalidInput("Invalid mint address".to_string()))?;
     
-    Ok(Json(ApiResponse::success(BalanceResponse {
-        address: params.address,
-        balance_lamports: balance,
-        balance_sol: balance as f64 / 1_000_000_000.0,
-    })))
+    let owner_pubkey = Pubkey::from_str(&payload.owner_address)
+        .map_err(|_| AppError::InvalidInput("Invalid owner address".to_string()))?;
+
+    let mint = state
+        .database
+        .get_mint_info(&payload.mint_address)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Mint not found".to_string()))?;
+
+    let freeze_authority = mint
+        .freeze_authority
+        .ok_or_else(|| AppError::InvalidInput("Mint has no freeze authority".to_string()))?;
+
+    Ok((mint_pubkey, owner_pubkey, freeze_authority))
 }
 
-// Create a new token mint
-async fn create_mint(
+async fn set_account_frozen(
+    state: AppState,
+    user: AuthUser,
+    headers: HeaderMap,
+    payload: FreezeAccountRequest,
+    action: FreezeAction,
+) -> Result<Json<ApiResponse<TransactionResponse>>> {
+    let (mint_pubkey, owner_pubkey, freeze_authority) =
+        freeze_authority_for(&state, &user, &payload).await?;
+
+    let key = idempotency_key(&headers, payload.idempotency_key.as_deref());
+    let result = run_once(&state, &user, action.as_str(), key, &payload, || async {
+        let built = state.blockchain.set_account_frozen(
+            action,
+            &mint_pubkey,
+            &owner_pubkey,
+            &freeze_authority,
+            &payload.fees,
+        ).await?;
+
+        let transaction_record =
+            freeze_transaction_record(&user, action, &payload, &freeze_authority, &built);
+
+        broadcast_recorded(&state, built, &transaction_record).await
+    }).await?;
+
+    Ok(Json(ApiResponse::success(result)))
+}
+
+async fn prepare_set_account_frozen(
+    state: AppState,
+    user: AuthUser,
+    payload: FreezeAccountRequest,
+    action: FreezeAction,
+) -> Result<Json<ApiResponse<PreparedTransactionResponse>>> {
+    let (mint_pubkey, owner_pubkey, freeze_authority) =
+        freeze_authority_for(&state, &user, &payload).await?;
+
+    let built = state.blockchain.prepare_set_account_frozen(
+        action,
+        &mint_pubkey,
+        &owner_pubkey,
+        &freeze_authority,
+        &payload.fees,
+    ).await?;
+
+    let response = prepared_response(&state, &built)?;
+    let transaction_record =
+        freeze_transaction_record(&user, action, &payload, &freeze_authority, &built);
+
+    state.prepared.insert(&built, transaction_record).await?;
+
+    Ok(Json(ApiResponse::success(response)))
+}
+
+// Freeze a holder's token account
+async fn freeze_token_account(
     State(state): State<AppState>,
-    Json(payload): Json<CreateMintRequest>,
-) -> Result<Json<ApiResponse<MintResponse>>> {
-    let result = state.blockchain.create_mint(
-        payload.decimals,
-        &payload.mint_authority,
-        payload.freeze_authority.as_deref(),
+    user: AuthUser,
+    headers: HeaderMap,
+    Json(payload): Json<FreezeAccountRequest>,
+) -> Result<Json<ApiResponse<TransactionResponse>>> {
+    set_account_frozen(state, user, headers, payload, FreezeAction::Freeze).await
+}
+
+// Thaw a previously frozen token account
+async fn thaw_token_account(
+    State(state): State<AppState>,
+    user: AuthUser,
+    headers: HeaderMap,
+    Json(payload): Json<FreezeAccountRequest>,
+) -> Result<Json<ApiResponse<TransactionResponse>>> {
+    set_account_frozen(state, user, headers, payload, FreezeAction::Thaw).await
+}
+
+// Build a freeze transaction for the freeze authority's wallet to sign
+async fn prepare_freeze_token_account(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(payload): Json<FreezeAccountRequest>,
+) -> Result<Json<ApiResponse<PreparedTransactionResponse>>> {
+    prepare_set_account_frozen(state, user, payload, FreezeAction::Freeze).await
+}
+
+// Build a thaw transaction for the freeze authority's wallet to sign
+async fn prepare_thaw_token_account(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(payload): Json<FreezeAccountRequest>,
+) -> Result<Json<ApiResponse<PreparedTransactionResponse>>> {
+    prepare_set_account_frozen(state, user, payload, FreezeAction::Thaw).await
+}
+
+// Only admins manage mint authorities. Returns the mint, the authority being
+// replaced (from `token_mints`) and the new authority, if any.
+async fn authority_change_for(
+    state: &AppState,
+    user: &AuthUser,
+    payload: &SetAuthorityRequest,
+) -> Result<(Pubkey, String, Option<Pubkey>)> {
+    if !user.is_admin() {
+        return Err(AppError::Forbidden("Only admins can change mint authorities".to_string()));
+    }
+
+    let mint_pubkey = Pubkey::from_str(&payload.mint_address)
+        .map_err(|_| AppError::InvalidInput("Invalid mint address".to_string()))?;
+
+    let new_authority = match (&payload.new_authority, payload.revoke) {
+        (Some(new_authority), false) => Some(
+            Pubkey::from_str(new_authority)
+                .map_err(|_| AppError::InvalidInput("Invalid new authority address".to_string()))?,
+        ),
+        (None, true) => None,
+        _ => {
+            return Err(AppError::InvalidInput(
+                "Provide either new_authority or revoke: true".to_string(),
+            ))
+        }
+    };
+
+    let mint = state
+        .database
+        .get_mint_info(&payload.mint_address)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Mint not found".to_string()))?;
+
+    let current_authority = match payload.authority_type {
+        AuthorityKind::Mint => mint.mint_authority,
+        AuthorityKind::Freeze => mint.freeze_authority,
+    }
+    .ok_or_else(|| {
+        AppError::InvalidInput(format!(
+            "Mint has no {} authority to change",
+            payload.authority_type.as_str()
+        ))
+    })?;
+
+    Ok((mint_pubkey, current_authority, new_authority))
+}
+
+// Rotate or revoke a mint's mint or freeze authority
+async fn set_mint_authority(
+    State(state): State<AppState>,
+    user: AuthUser,
+    headers: HeaderMap,
+    Json(payload): Json<SetAuthorityRequest>,
+) -> Result<Json<ApiResponse<TransactionResponse>>> {
+    let (mint_pubkey, current_authority, new_authority) =
+        authority_change_for(&state, &user, &payload).await?;
+
+    let key = idempotency_key(&headers, payload.idempotency_key.as_deref());
+    let result = run_once(&state, &user, "set_authority", key, &payload, || async {
+        let built = state.blockchain.set_mint_authority(
+            &mint_pubkey,
+            payload.authority_type,
+            &current_authority,
+            new_authority.as_ref(),
+            &payload.fees,
+        ).await?;
+
+        let transaction_record =
+            set_authority_transaction_record(&user, &payload, &current_authority, &built);
+
+        broadcast_recorded(&state, built, &transaction_record).await
+    }).await?;
+
+    Ok(Json(ApiResponse::success(result)))
+}
+
+// Change the name, symbol or URI of a mint's token metadata
+async fn update_token_metadata(
+    State(state): State<AppState>,
+    user: AuthUser,
+    headers: HeaderMap,
+    Json(payload): Json<UpdateMetadataRequest>,
+) -> Result<Json<ApiResponse<TransactionResponse>>> {
+    if !user.is_admin() {
+        return Err(AppError::Forbidden("Only admins can update token metadata".to_string()));
+    }
+
+    let mint_pubkey = Pubkey::from_str(&payload.mint_address)
+        .map_err(|_| AppError::InvalidInput("Invalid mint address".to_string()))?;
+
+    if payload.name.is_none() && payload.symbol.is_none() && payload.uri.is_none() {
+        return Err(AppError::InvalidInput(
+            "Provide at least one of name, symbol or uri".to_string(),
+        ));
+    }
+
+    let key = idempotency_key(&headers, payload.idempotency_key.as_deref());
+    let result = run_once(&state, &user, "update_metadata", key, &payload, || async {
+        let (built, metadata) = state
+            .blockchain
+            .update_token_metadata(&mint_pubkey, &payload)
+            .await?;
+
+        let transaction_record = metadata_transaction_record(&user, &payload, &metadata, &built);
+
+        broadcast_recorded(&state, built, &transaction_record).await
+    }).await?;
+
+    Ok(Json(ApiResponse::success(result)))
+}
+
+// Build an authority change for the current authority's wallet to sign
+async fn prepare_set_mint_authority(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(payload): Json<SetAuthorityRequest>,
+) -> Result<Json<ApiResponse<PreparedTransactionResponse>>> {
+    let (mint_pubkey, current_authority, new_authority) =
+        authority_change_for(&state, &user, &payload).await?;
+
+    let built = state.blockchain.prepare_set_mint_authority(
+        &mint_pubkey,
+        payload.authority_type,
+        &current_authority,
+        new_authority.as_ref(),
+        &payload.fees,
     ).await?;
 
-    // Store mint info in database
-    state.database.store_mint_info(&result).await?;
+    let response = prepared_response(&state, &built)?;
+    let transaction_record =
+        set_authority_transaction_record(&user, &payload, &current_authority, &built);
+
+    state.prepared.insert(&built, transaction_record).await?;
+
+    Ok(Json(ApiResponse::success(response)))
+}
+
+// Transfer SOL out of the service payer
+async fn transfer_sol(
+    State(state): State<AppState>,
+    user: AuthUser,
+    headers: HeaderMap,
+    Json(payload): Json<SolTransferRequest>,
+) -> Result<Json<ApiResponse<TransactionResponse>>> {
+    user.authorize(payload.user_id)?;
+
+    let lamports = sol_amount_lamports(&payload.amount)?;
+
+    let from_pubkey = Pubkey::from_str(&payload.from_address)
+        .map_err(|_| AppError::InvalidInput("Invalid from address".to_string()))?;
+    
+    let to_pubkey = Pubkey::from_str(&payload.to_address)
+        .map_err(|_| AppError::InvalidInput("Invalid to address".to_string()))?;
+
+    // The payer's own SOL funds every transaction, so only admins may move it
+    if from_pubkey == state.blockchain.payer_pubkey() && !user.is_admin() {
+        return Err(AppError::Forbidden(
+            "Only admins can transfer SOL from the fee payer".to_string(),
+        ));
+    }
+
+    let key = idempotency_key(&headers, payload.idempotency_key.as_deref());
+    let result = run_once(&state, &user, "transfer_sol", key, &payload, || async {
+        let built = state.blockchain.transfer_sol(
+            &from_pubkey,
+            &to_pubkey,
+            lamports,
+            &payload.fees,
+        ).await?;
+
+        let transaction_record = sol_transfer_record(
+            payload.user_id,
+            payload.from_address.clone(),
+            payload.to_address.clone(),
+            lamports,
+            &built,
+        );
+
+        broadcast_recorded(&state, built, &transaction_record).await
+    }).await?;
 
     Ok(Json(ApiResponse::success(result)))
 }
 
-// Mint tokens to an address
-async fn mint_tokens(
+// Build a SOL transfer for the sender's wallet to sign
+async fn prepare_transfer_sol(
     State(state): State<AppState>,
-    Json(payload): Json<MintTokensRequest>,
+    user: AuthUser,
+    Json(payload): Json<SolTransferRequest>,
+) -> Result<Json<ApiResponse<PreparedTransactionResponse>>> {
+    user.authorize(payload.user_id)?;
+
+    let lamports = sol_amount_lamports(&payload.amount)?;
+
+    let from_pubkey = Pubkey::from_str(&payload.from_address)
+        .map_err(|_| AppError::InvalidInput("Invalid from address".to_string()))?;
+    
+    let to_pubkey = Pubkey::from_str(&payload.to_address)
+        .map_err(|_| AppError::InvalidInput("Invalid to address".to_string()))?;
+
+    // The payer's own SOL funds every transaction, so only admins may move it
+    if from_pubkey == state.blockchain.payer_pubkey() && !user.is_admin() {
+        return Err(AppError::Forbidden(
+            "Only admins can transfer SOL from the fee payer".to_string(),
+        ));
+    }
+
+    let built = state.blockchain.prepare_transfer_sol(
+        &from_pubkey,
+        &to_pubkey,
+        lamports,
+        &payload.fees,
+    ).await?;
+
+    let response = prepared_response(&state, &built)?;
+    let transaction_record = sol_transfer_record(
+        payload.user_id,
+        payload.from_address.clone(),
+        payload.to_address.clone(),
+        lamports,
+        &built,
+    );
+
+    state.prepared.insert(&built, transaction_record).await?;
+
+    Ok(Json(ApiResponse::success(response)))
+}
+
+// Top up the fee payer from the treasury wallet
+async fn fund_payer(
+    State(state): State<AppState>,
+    user: AuthUser,
+    headers: HeaderMap,
+    Json(payload): Json<FundPayerRequest>,
+) -> Result<Json<ApiResponse<TransactionResponse>>> {
+    if !user.is_admin() {
+        return Err(AppError::Forbidden("Only admins can fund the payer".to_string()));
+    }
+
+    let lamports = sol_amount_lamports(&payload.amount)?;
+
+    let treasury = state
+        .blockchain
+        .treasury_pubkey()
+        .ok_or_else(|| AppError::InvalidInput("No treasury wallet is configured".to_string()))?;
+
+    let key = idempotency_key(&headers, payload.idempotency_key.as_deref());
+    let result = run_once(&state, &user, "fund_payer", key, &payload, || async {
+        let built = state.blockchain.fund_payer(lamports, &payload.fees).await?;
+
+        let transaction_record = sol_transfer_record(
+            user.user_id,
+            treasury.to_string(),
+            state.blockchain.payer_pubkey().to_string(),
+            lamports,
+            &built,
+        );
+
+        broadcast_recorded(&state, built, &transaction_record).await
+    }).await?;
+
+    Ok(Json(ApiResponse::success(result)))
+}
+
+// Broadcast a prepared transaction after the wallet has signed it
+async fn submit_transaction(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(payload): Json<SubmitTransactionRequest>,
 ) -> Result<Json<ApiResponse<TransactionResponse>>> {
+    let transaction = BlockchainService::decode_transaction(&payload.transaction)?;
+
+    let (transaction_record, built) = state.prepared.get(transaction).await?;
+    user.authorize(transaction_record.user_id)?;
+
+    state.blockchain.verify_signed_transaction(&built.transaction)?;
+    state.prepared.remove(&built.transaction).await?;
+
+    let result = broadcast_recorded(&state, built, &transaction_record).await?;
+
+    Ok(Json(ApiResponse::success(result)))
+}
+
+// Dry-run mint creation without broadcasting
+async fn simulate_create_mint(
+    State(state): State<AppState>,
+    Json(payload): Json<CreateMintRequest>,
+) -> Result<Json<ApiResponse<SimulationResponse>>> {
+    let result = state.blockchain.simulate_create_mint(&payload).await?;
+
+    Ok(Json(ApiResponse::success(result)))
+}
+
+// Dry-run a mint without broadcasting or recording it
+async fn simulate_mint_tokens(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(payload): Json<MintTokensRequest>,
+) -> Result<Json<ApiResponse<SimulationResponse>>> {
+    user.authorize(payload.user_id)?;
+
     let mint_pubkey = Pubkey::from_str(&payload.mint_address)
         .map_err(|_| AppError::InvalidInput("Invalid mint address".to_string()))?;
     
     let destination_pubkey = Pubkey::from_str(&payload.destination_address)
         .map_err(|_| AppError::InvalidInput("Invalid destination address".to_string()))?;
 
-    let result = state.blockchain.mint_tokens(
+    let amount = token_amount(&state, &mint_pubkey, payload.amount, payload.ui_amount.as_deref()).await?;
+
+    let result = state.blockchain.simulate_mint_tokens(
         &mint_pubkey,
         &destination_pubkey,
-        payload.amount,
+        amount,
         &payload.authority,
+        &payload.fees,
     ).await?;
 
-    // Store transaction in database
-    let transaction_record = TransactionRecord {
-        id: Uuid::new_v4(),
-        user_id: payload.user_id,
-        transaction_hash: result.signature.clone(),
-        transaction_type: "mint".to_string(),
-        amount: Some(payload.amount as f64),
-        token_address: Some(payload.mint_address),
-        from_address: None,
-        to_address: Some(payload.destination_address),
-        status: "confirmed".to_string(),
-        block_number: result.slot,
-        metadata: serde_json::json!({
-            "mint_authority": payload.authority,
-            "amount": payload.amount
-        }),
-    };
-
-    state.database.store_transaction(&transaction_record).await?;
-
     Ok(Json(ApiResponse::success(result)))
 }
 
-// Transfer tokens between addresses
-async fn transfer_tokens(
+// Dry-run a transfer without broadcasting or recording it
+async fn simulate_transfer_tokens(
     State(state): State<AppState>,
+    user: AuthUser,
     Json(payload): Json<TransferTokensRequest>,
-) -> Result<Json<ApiResponse<TransactionResponse>>> {
+) -> Result<Json<ApiResponse<SimulationResponse>>> {
+    user.authorize(payload.user_id)?;
+
     let mint_pubkey = Pubkey::from_str(&payload.mint_address)
         .map_err(|_| AppError::InvalidInput("Invalid mint address".to_string()))?;
     
@@ -154,42 +2347,76 @@ async fn transfer_tokens(
     let to_pubkey = Pubkey::from_str(&payload.to_address)
         .map_err(|_| AppError::InvalidInput("Invalid to address".to_string()))?;
 
-    let result = state.blockchain.transfer_tokens(
+    let amount = token_amount(&state, &mint_pubkey, payload.amount, payload.ui_amount.as_deref()).await?;
+
+    let result = state.blockchain.simulate_transfer_tokens(
         &mint_pubkey,
         &from_pubkey,
         &to_pubkey,
-        payload.amount,
+        amount,
         &payload.owner,
+        &payload.fees,
     ).await?;
 
-    // Store transaction in database
-    let transaction_record = TransactionRecord {
-        id: Uuid::new_v4(),
-        user_id: payload.user_id,
-        transaction_hash: result.signature.clone(),
-        transaction_type: "transfer".to_string(),
-        amount: Some(payload.amount as f64),
-        token_address: Some(payload.mint_address),
-        from_address: Some(payload.from_address),
-        to_address: Some(payload.to_address),
-        status: "confirmed".to_string(),
-        block_number: result.slot,
-        metadata: serde_json::json!({
-            "owner": payload.owner,
-            "amount": payload.amount
-        }),
-    };
+    Ok(Json(ApiResponse::success(result)))
+}
+
+// Mint details: the stored record merged with on-chain state
+async fn get_mint(
+    Path(address): Path<String>,
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<MintInfoResponse>>> {
+    let mint_pubkey = Pubkey::from_str(&address)
+        .map_err(|_| AppError::InvalidInput("Invalid mint address".to_string()))?;
 
-    state.database.store_transaction(&transaction_record).await?;
+    let (mint, token_program) = state.blockchain.get_mint_account(&mint_pubkey).await?;
+    let record = state.database.get_mint_info(&address).await?;
 
-    Ok(Json(ApiResponse::success(result)))
+    Ok(Json(ApiResponse::success(MintInfoResponse {
+        mint_address: address,
+        supply: TokenAmount::new(mint.supply, mint.decimals),
+        mint_authority: Option::<Pubkey>::from(mint.mint_authority).map(|key| key.to_string()),
+        freeze_authority: Option::<Pubkey>::from(mint.freeze_authority).map(|key| key.to_string()),
+        is_initialized: mint.is_initialized,
+        token_program,
+        record,
+    })))
+}
+
+// Token balances for a user, derived from their recorded transactions
+async fn get_user_balances(
+    Path(user_id): Path<Uuid>,
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> Result<Json<ApiResponse<Vec<UserTokenBalance>>>> {
+    user.authorize(user_id)?;
+
+    let balances = state.database.get_user_token_balances(user_id).await?;
+
+    Ok(Json(ApiResponse::success(balances)))
+}
+
+// Every SPL token account held by a wallet
+async fn get_token_accounts(
+    Query(params): Query<TokenAccountsQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<Vec<TokenAccountInfo>>>> {
+    let owner_pubkey = Pubkey::from_str(&params.owner)
+        .map_err(|_| AppError::InvalidInput("Invalid owner address".to_string()))?;
+
+    let accounts = state.blockchain.get_token_accounts(&owner_pubkey).await?;
+
+    Ok(Json(ApiResponse::success(accounts)))
 }
 
 // Get transaction history for a user
 async fn get_transactions(
     Query(params): Query<TransactionQuery>,
     State(state): State<AppState>,
+    user: AuthUser,
 ) -> Result<Json<ApiResponse<Vec<TransactionRecord>>>> {
+    user.authorize(params.user_id)?;
+
     let transactions = state.database.get_user_transactions(
         params.user_id,
         params.limit.unwrap_or(50),
@@ -206,8 +2433,10 @@ async fn verify_transaction(
 ) -> Result<Json<ApiResponse<TransactionStatus>>> {
     let status = state.blockchain.get_transaction_status(&params.signature).await?;
     
-    // Update transaction status in database
-    state.database.update_transaction_status(&params.signature, &status.status).await?;
+    // Update transaction status in database once the outcome is final
+    if status.status == "confirmed" || status.status == "failed" {
+        state.database.update_transaction_status(&params.signature, &status.status, status.slot).await?;
+    }
 
     Ok(Json(ApiResponse::success(status)))
 }
@@ -222,21 +2451,78 @@ pub async fn create_app() -> Result<Router> {
     // Initialize services
     let blockchain = Arc::new(BlockchainService::new().await?);
     let database = Arc::new(DatabaseService::new().await?);
+    let api_keys = Arc::new(ApiKeys::from_env()?);
+    let jwt = Arc::new(JwtVerifier::from_env().await?);
+
+    let prepared = Arc::new(PreparedTransactions::new(database.clone()));
 
     let state = AppState {
         blockchain,
         database,
+        api_keys,
+        jwt,
+        prepared,
+        airdrops: Arc::new(AirdropRunner::new(AirdropConfig::from_env())),
     };
 
-    // Build router
-    let app = Router::new()
-        .route("/health", get(health_check))
+    // Settle pending transactions, starting with any left by a previous run
+    reconciler::spawn(state.clone(), ReconcilerConfig::from_env());
+    airdrop::resume(state.clone());
+
+    // Routes that require a valid API key
+    let protected = Router::new()
         .route("/balance", get(get_balance))
+        .route("/balances", get(get_balances))
         .route("/mint/create", post(create_mint))
+        .route("/mint/create/simulate", post(simulate_create_mint))
         .route("/mint/tokens", post(mint_tokens))
+        .route("/mint/tokens/prepare", post(prepare_mint_tokens))
+        .route("/mint/tokens/simulate", post(simulate_mint_tokens))
+        .route("/mint/authority", post(set_mint_authority))
+        .route("/mint/authority/prepare", post(prepare_set_mint_authority))
+        .route("/mint/metadata", post(update_token_metadata))
         .route("/transfer", post(transfer_tokens))
+        .route("/transfer/prepare", post(prepare_transfer_tokens))
+        .route("/transfer/simulate", post(simulate_transfer_tokens))
+        .route("/burn", post(burn_tokens))
+        .route("/burn/prepare", post(prepare_burn_tokens))
+        .route("/sol/transfer", post(transfer_sol))
+        .route("/sol/transfer/prepare", post(prepare_transfer_sol))
+        .route("/payer/fund", post(fund_payer))
+        .route("/memberships", post(grant_membership))
+        .route("/tickets", post(issue_ticket))
+        .route("/tickets/check-in", post(check_in_ticket))
+        .route("/collections", post(create_collection))
+        .route("/airdrops", post(create_airdrop))
+        .route("/airdrops/:id", get(get_airdrop))
+        .route("/airdrops/:id/recipients", get(get_airdrop_recipients))
+        .route("/campaigns", post(create_campaign))
+        .route("/campaigns/claim", post(claim_campaign))
+        .route("/campaigns/:id", get(get_campaign))
+        .route("/campaigns/:id/proof", get(get_campaign_proof))
+        .route("/collections/items", post(mint_collection_item))
+        .route("/collections/verify", post(verify_collection_item))
+        .route("/collections/:address/items", get(get_collection_items))
+        .route("/collections/:address/ownership", get(get_collection_ownership))
+        .route("/accounts/freeze", post(freeze_token_account))
+        .route("/accounts/freeze/prepare", post(prepare_freeze_token_account))
+        .route("/accounts/thaw", post(thaw_token_account))
+        .route("/accounts/thaw/prepare", post(prepare_thaw_token_account))
+        .route("/transactions/submit", post(submit_transaction))
         .route("/transactions", get(get_transactions))
+        .route("/mints/:address", get(get_mint))
+        .route("/users/:id/balances", get(get_user_balances))
+        .route("/token-accounts", get(get_token_accounts))
         .route("/verify", get(verify_transaction))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            auth::require_api_key,
+        ));
+
+    // Build router
+    let app = Router::new()
+        .route("/health", get(health_check))
+        .merge(protected)
         .layer(CorsLayer::permissive())
         .with_state(state);
 
@@ -0,0 +1,129 @@
+use solana_sdk::{
+    hash::{hashv, Hash},
+    pubkey::Pubkey,
+};
+
+// Domain prefixes keep a leaf from ever hashing like an internal node
+const LEAF_PREFIX: &[u8] = &[0];
+const INTERMEDIATE_PREFIX: &[u8] = &[1];
+
+// Leaf committing to a wallet's allocation in raw base units
+pub fn leaf_hash(wallet: &Pubkey, amount: u64) -> Hash {
+    hashv(&[LEAF_PREFIX, wallet.as_ref(), &amount.to_le_bytes()])
+}
+
+// Children are hashed in sorted order, so a proof is just the sibling hashes
+// without left/right markers
+fn parent_hash(a: &Hash, b: &Hash) -> Hash {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+    hashv(&[INTERMEDIATE_PREFIX, left.as_ref(), right.as_ref()])
+}
+
+// Binary Merkle tree kept level by level, leaves first. A node without a
+// sibling is carried up to the next level unchanged.
+pub struct MerkleTree {
+    levels: Vec<Vec<Hash>>,
+}
+
+impl MerkleTree {
+    // `leaves` must not be empty
+    pub fn new(leaves: Vec<Hash>) -> Self {
+        let mut levels = vec![leaves];
+
+        while levels[levels.len() - 1].len() > 1 {
+            let next = levels[levels.len() - 1]
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => parent_hash(left, right),
+                    [single] => *single,
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(next);
+        }
+
+        Self { levels }
+    }
+
+    pub fn root(&self) -> Hash {
+        self.levels[self.levels.len() - 1][0]
+    }
+
+    // Sibling hashes from the leaf at `index` up to the root
+    pub fn proof(&self, mut index: usize) -> Vec<Hash> {
+        let mut proof = vec![];
+
+        for level in &self.levels[..self.levels.len() - 1] {
+            if let Some(sibling) = level.get(index ^ 1) {
+                proof.push(*sibling);
+            }
+            index /= 2;
+        }
+
+        proof
+    }
+}
+
+pub fn verify(proof: &[Hash], root: &Hash, leaf: Hash) -> bool {
+    proof.iter().fold(leaf, |node, sibling| parent_hash(&node, sibling)) == *root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn allocations(count: usize) -> Vec<(Pubkey, u64)> {
+        (0..count).map(|i| (Pubkey::new_unique(), 1_000 * (i as u64 + 1))).collect()
+    }
+
+    fn tree_of(allocations: &[(Pubkey, u64)]) -> MerkleTree {
+        MerkleTree::new(allocations.iter().map(|(wallet, amount)| leaf_hash(wallet, *amount)).collect())
+    }
+
+    #[test]
+    fn every_leaf_proves_against_the_root() {
+        for count in [1, 2, 3, 5] {
+            let allocations = allocations(count);
+            let tree = tree_of(&allocations);
+            let root = tree.root();
+
+            for (index, (wallet, amount)) in allocations.iter().enumerate() {
+                assert!(
+                    verify(&tree.proof(index), &root, leaf_hash(wallet, *amount)),
+                    "leaf {} of {} did not verify",
+                    index,
+                    count
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn single_leaf_is_its_own_root() {
+        let allocations = allocations(1);
+        let tree = tree_of(&allocations);
+
+        assert_eq!(tree.root(), leaf_hash(&allocations[0].0, allocations[0].1));
+        assert!(tree.proof(0).is_empty());
+    }
+
+    #[test]
+    fn tampered_amount_fails() {
+        let allocations = allocations(5);
+        let tree = tree_of(&allocations);
+        let root = tree.root();
+
+        for (index, (wallet, amount)) in allocations.iter().enumerate() {
+            assert!(!verify(&tree.proof(index), &root, leaf_hash(wallet, amount + 1)));
+        }
+    }
+
+    #[test]
+    fn proof_does_not_carry_to_another_wallet() {
+        let allocations = allocations(3);
+        let tree = tree_of(&allocations);
+
+        let (_, amount) = allocations[0];
+        assert!(!verify(&tree.proof(0), &tree.root(), leaf_hash(&allocations[1].0, amount)));
+    }
+}
@@ -1,12 +1,32 @@
+use crate::amount::TokenAmount;
+use bigdecimal::BigDecimal;
 use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
 use sqlx::FromRow;
 use uuid::Uuid;
 
 // Request/Response models for API endpoints
 
+// `mint` is an optional comma-separated list of token mints
 #[derive(Debug, Deserialize)]
 pub struct BalanceQuery {
     pub address: String,
+    pub mint: Option<String>,
+}
+
+// Both fields are comma-separated lists
+#[derive(Debug, Deserialize)]
+pub struct BalancesQuery {
+    pub addresses: String,
+    pub mints: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TokenBalance {
+    pub mint: String,
+    pub token_account: String,
+    #[serde(flatten)]
+    pub balance: TokenAmount,
 }
 
 #[derive(Debug, Serialize)]
@@ -14,50 +34,542 @@ pub struct BalanceResponse {
     pub address: String,
     pub balance_lamports: u64,
     pub balance_sol: f64,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tokens: Vec<TokenBalance>,
 }
 
-#[derive(Debug, Deserialize)]
+// Optional compute-budget settings accepted on every transaction request.
+// Unset fields fall back to the service-wide defaults.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PriorityFeeOptions {
+    pub compute_unit_limit: Option<u32>,
+    // Micro-lamports per compute unit
+    pub compute_unit_price: Option<u64>,
+    // Derive the price from recent prioritization fees at this percentile
+    pub priority_fee_percentile: Option<u8>,
+}
+
+// Token program a mint belongs to. Legacy SPL Token is the default;
+// Token-2022 is needed for mints with extensions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TokenProgram {
+    #[default]
+    #[serde(rename = "spl_token")]
+    SplToken,
+    #[serde(rename = "token_2022")]
+    Token2022,
+}
+
+impl TokenProgram {
+    pub fn id(&self) -> Pubkey {
+        match self {
+            TokenProgram::SplToken => spl_token::id(),
+            TokenProgram::Token2022 => spl_token_2022::id(),
+        }
+    }
+
+    pub fn from_id(program_id: &Pubkey) -> Option<Self> {
+        if *program_id == spl_token::id() {
+            Some(TokenProgram::SplToken)
+        } else if *program_id == spl_token_2022::id() {
+            Some(TokenProgram::Token2022)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TokenProgram::SplToken => "spl_token",
+            TokenProgram::Token2022 => "token_2022",
+        }
+    }
+}
+
+// Token-2022 extensions set up when a mint is created
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MintExtensions {
+    // Soulbound: tokens can be minted and burned but never transferred
+    #[serde(default)]
+    pub non_transferable: bool,
+    // May transfer or burn any holder's tokens, e.g. to revoke a badge
+    pub permanent_delegate: Option<String>,
+}
+
+impl MintExtensions {
+    pub fn is_empty(&self) -> bool {
+        !self.non_transferable && self.permanent_delegate.is_none()
+    }
+}
+
+// Metaplex token metadata shown by wallets and explorers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenMetadata {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+}
+
+// `name` and `symbol` are needed to create token metadata; `uri` points at
+// the off-chain JSON with the image and description
+#[derive(Debug, Serialize, Deserialize)]
 pub struct CreateMintRequest {
     pub decimals: u8,
+    #[serde(default)]
+    pub token_program: TokenProgram,
+    #[serde(flatten)]
+    pub extensions: MintExtensions,
+    pub name: Option<String>,
+    pub symbol: Option<String>,
+    pub uri: Option<String>,
     pub mint_authority: String,
     pub freeze_authority: Option<String>,
+    #[serde(flatten)]
+    pub fees: PriorityFeeOptions,
+    #[serde(default, skip_serializing)]
+    pub idempotency_key: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct MintResponse {
     pub mint_address: String,
     pub signature: String,
     pub decimals: u8,
-    pub mint_authority: String,
+    // `None` once the mint authority has been revoked
+    pub mint_authority: Option<String>,
     pub freeze_authority: Option<String>,
+    #[serde(default)]
+    pub token_program: TokenProgram,
+    #[serde(flatten)]
+    pub extensions: MintExtensions,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<TokenMetadata>,
 }
 
-#[derive(Debug, Deserialize)]
+// Fields left out keep their current on-chain value
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateMetadataRequest {
+    pub mint_address: String,
+    pub name: Option<String>,
+    pub symbol: Option<String>,
+    pub uri: Option<String>,
+    #[serde(flatten)]
+    pub fees: PriorityFeeOptions,
+    #[serde(default, skip_serializing)]
+    pub idempotency_key: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct MintTokensRequest {
     pub user_id: Uuid,
     pub mint_address: String,
     pub destination_address: String,
-    pub amount: u64,
+    // Exactly one of `amount` (raw base units) or `ui_amount` (e.g. "12.5")
+    pub amount: Option<u64>,
+    pub ui_amount: Option<String>,
     pub authority: String,
+    #[serde(flatten)]
+    pub fees: PriorityFeeOptions,
+    #[serde(default, skip_serializing)]
+    pub idempotency_key: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct TransferTokensRequest {
     pub user_id: Uuid,
     pub mint_address: String,
     pub from_address: String,
     pub to_address: String,
-    pub amount: u64,
+    // Exactly one of `amount` (raw base units) or `ui_amount` (e.g. "12.5")
+    pub amount: Option<u64>,
+    pub ui_amount: Option<String>,
     pub owner: String,
+    #[serde(flatten)]
+    pub fees: PriorityFeeOptions,
+    #[serde(default, skip_serializing)]
+    pub idempotency_key: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BurnTokensRequest {
+    pub user_id: Uuid,
+    pub mint_address: String,
+    pub owner_address: String,
+    // Exactly one of `amount` (raw base units) or `ui_amount` (e.g. "12.5")
+    pub amount: Option<u64>,
+    pub ui_amount: Option<String>,
+    pub authority: String,
+    #[serde(flatten)]
+    pub fees: PriorityFeeOptions,
+    #[serde(default, skip_serializing)]
+    pub idempotency_key: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FreezeAccountRequest {
+    pub mint_address: String,
+    pub owner_address: String,
+    pub reason: Option<String>,
+    #[serde(flatten)]
+    pub fees: PriorityFeeOptions,
+    #[serde(default, skip_serializing)]
+    pub idempotency_key: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuthorityKind {
+    Mint,
+    Freeze,
+}
+
+impl AuthorityKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuthorityKind::Mint => "mint",
+            AuthorityKind::Freeze => "freeze",
+        }
+    }
+}
+
+// Either `new_authority` or `revoke: true` must be given; revoking is
+// permanent, so it is never implied by a missing field
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetAuthorityRequest {
+    pub mint_address: String,
+    pub authority_type: AuthorityKind,
+    pub new_authority: Option<String>,
+    #[serde(default)]
+    pub revoke: bool,
+    #[serde(flatten)]
+    pub fees: PriorityFeeOptions,
+    #[serde(default, skip_serializing)]
+    pub idempotency_key: Option<String>,
+}
+
+// Exactly one of `amount_lamports` or `amount_sol` must be given, mirroring
+// the two units in `BalanceResponse`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SolAmount {
+    pub amount_lamports: Option<u64>,
+    pub amount_sol: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SolTransferRequest {
+    pub user_id: Uuid,
+    pub from_address: String,
+    pub to_address: String,
+    #[serde(flatten)]
+    pub amount: SolAmount,
+    #[serde(flatten)]
+    pub fees: PriorityFeeOptions,
+    #[serde(default, skip_serializing)]
+    pub idempotency_key: Option<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FundPayerRequest {
+    #[serde(flatten)]
+    pub amount: SolAmount,
+    #[serde(flatten)]
+    pub fees: PriorityFeeOptions,
+    #[serde(default, skip_serializing)]
+    pub idempotency_key: Option<String>,
+}
+
+// Grants the membership badge of `mint_address` to the user's primary wallet
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GrantMembershipRequest {
+    pub user_id: Uuid,
+    pub mint_address: String,
+    #[serde(flatten)]
+    pub fees: PriorityFeeOptions,
+    #[serde(default, skip_serializing)]
+    pub idempotency_key: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MembershipResponse {
+    pub user_id: Uuid,
+    pub mint_address: String,
+    pub wallet_address: String,
+    pub transaction: TransactionResponse,
+}
+
+// Issues the NFT ticket for an event registration to the attendee's primary
+// wallet. `uri` points at the ticket's off-chain metadata JSON.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IssueTicketRequest {
+    pub registration_id: Uuid,
+    pub uri: Option<String>,
+    #[serde(flatten)]
+    pub fees: PriorityFeeOptions,
+    #[serde(default, skip_serializing)]
+    pub idempotency_key: Option<String>,
+}
+
+// Redeems the ticket held by `wallet_address` at the door
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CheckInTicketRequest {
+    pub mint_address: String,
+    pub wallet_address: String,
+    #[serde(flatten)]
+    pub fees: PriorityFeeOptions,
+    #[serde(default, skip_serializing)]
+    pub idempotency_key: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TicketResponse {
+    pub ticket_id: Uuid,
+    pub registration_id: Uuid,
+    pub event_id: Uuid,
+    pub user_id: Uuid,
+    pub mint_address: String,
+    pub wallet_address: String,
+    pub status: String,
+    pub transaction: TransactionResponse,
+}
+
+// Creates a collection NFT held and administered by the service payer
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateCollectionRequest {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    #[serde(flatten)]
+    pub fees: PriorityFeeOptions,
+    #[serde(default, skip_serializing)]
+    pub idempotency_key: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CollectionResponse {
+    pub collection_id: Uuid,
+    pub mint_address: String,
+    #[serde(flatten)]
+    pub metadata: TokenMetadata,
+    pub transaction: TransactionResponse,
+}
+
+// Mints an NFT into a collection for `owner_address`, or for the primary
+// wallet of `user_id` when no address is given
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MintCollectionItemRequest {
+    pub collection_address: String,
+    pub owner_address: Option<String>,
+    pub user_id: Option<Uuid>,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    #[serde(flatten)]
+    pub fees: PriorityFeeOptions,
+    #[serde(default, skip_serializing)]
+    pub idempotency_key: Option<String>,
+}
+
+// Verifies an item whose metadata already names the collection, e.g. one
+// minted outside this service
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VerifyCollectionItemRequest {
+    pub collection_address: String,
+    pub mint_address: String,
+    #[serde(flatten)]
+    pub fees: PriorityFeeOptions,
+    #[serde(default, skip_serializing)]
+    pub idempotency_key: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CollectionItemResponse {
+    pub collection_address: String,
+    pub mint_address: String,
+    pub owner_address: String,
+    pub user_id: Option<Uuid>,
+    pub transaction: TransactionResponse,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CollectionItemsQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CollectionOwnershipQuery {
+    pub item: String,
+    pub wallet: String,
+}
+
+// Read from chain, so it holds for items minted or transferred elsewhere
 #[derive(Debug, Serialize)]
+pub struct CollectionOwnershipResponse {
+    pub collection_address: String,
+    pub mint_address: String,
+    pub wallet_address: String,
+    pub in_collection: bool,
+    pub verified: bool,
+    pub holds_item: bool,
+    // Only true for a verified item the wallet currently holds
+    pub owns_collection_item: bool,
+}
+
+// One recipient of an airdrop, with the amount given like for a single mint
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AirdropRecipient {
+    pub destination: String,
+    pub amount: Option<u64>,
+    pub ui_amount: Option<String>,
+}
+
+// Mints to every recipient in the background. The service payer must be the
+// mint authority; the fee options apply to each transaction of the job.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateAirdropRequest {
+    pub mint_address: String,
+    pub recipients: Vec<AirdropRecipient>,
+    #[serde(flatten)]
+    pub fees: PriorityFeeOptions,
+    #[serde(default, skip_serializing)]
+    pub idempotency_key: Option<String>,
+}
+
+// Progress of an airdrop job, counted by recipient status
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct AirdropJobStatus {
+    pub id: Uuid,
+    pub mint_address: String,
+    pub status: String,
+    pub total_recipients: i32,
+    pub total_amount: TokenAmount,
+    pub pending: i64,
+    pub sending: i64,
+    pub confirmed: i64,
+    pub failed: i64,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub completed_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+// `status` optionally narrows the report to one recipient status
+#[derive(Debug, Deserialize)]
+pub struct AirdropRecipientsQuery {
+    pub status: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+// How a campaign pays out claims: minted by the service payer as mint
+// authority, or transferred from the payer's own token account
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CampaignDistribution {
+    #[default]
+    #[serde(rename = "mint")]
+    Mint,
+    #[serde(rename = "transfer")]
+    Transfer,
+}
+
+impl CampaignDistribution {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CampaignDistribution::Mint => "mint",
+            CampaignDistribution::Transfer => "transfer",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CampaignEntry {
+    pub wallet_address: String,
+    pub amount: Option<u64>,
+    pub ui_amount: Option<String>,
+}
+
+// Builds the Merkle tree over `entries` up front; nothing is sent on chain
+// until a wallet claims
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateCampaignRequest {
+    pub name: String,
+    pub mint_address: String,
+    #[serde(default)]
+    pub distribution: CampaignDistribution,
+    pub entries: Vec<CampaignEntry>,
+    #[serde(default, skip_serializing)]
+    pub idempotency_key: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CampaignProofQuery {
+    pub wallet: String,
+}
+
+// `proof` holds base58 sibling hashes from the leaf up to `merkle_root`
+#[derive(Debug, Serialize)]
+pub struct CampaignProofResponse {
+    pub campaign_id: Uuid,
+    pub merkle_root: String,
+    pub wallet_address: String,
+    pub index: i32,
+    pub amount: TokenAmount,
+    pub proof: Vec<String>,
+    pub status: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ClaimCampaignRequest {
+    pub campaign_id: Uuid,
+    pub wallet_address: String,
+    // Base58 sibling hashes as returned by the campaign proof endpoint
+    pub proof: Vec<String>,
+    #[serde(flatten)]
+    pub fees: PriorityFeeOptions,
+    #[serde(default, skip_serializing)]
+    pub idempotency_key: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CampaignClaimResponse {
+    pub campaign_id: Uuid,
+    pub wallet_address: String,
+    pub amount: TokenAmount,
+    pub transaction: TransactionResponse,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct TransactionResponse {
     pub signature: String,
     pub slot: Option<u64>,
     pub status: String,
 }
 
+#[derive(Debug, Serialize)]
+pub struct PreparedTransactionResponse {
+    pub transaction: String,
+    pub signature: String,
+    pub signers: Vec<String>,
+    pub recent_blockhash: String,
+    pub last_valid_block_height: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SubmitTransactionRequest {
+    pub transaction: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SimulationResponse {
+    pub success: bool,
+    pub error: Option<String>,
+    pub logs: Vec<String>,
+    pub units_consumed: Option<u64>,
+    pub fee_lamports: u64,
+    pub creates_token_account: bool,
+    pub rent_lamports: u64,
+    pub compute_unit_limit: Option<u32>,
+    pub compute_unit_price: Option<u64>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct TransactionQuery {
     pub user_id: Uuid,
@@ -80,39 +592,185 @@ pub struct TransactionStatus {
 
 // Database models
 
-#[derive(Debug, Serialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct TransactionRecord {
     pub id: Uuid,
     pub user_id: Uuid,
     pub transaction_hash: String,
     pub transaction_type: String,
-    pub amount: Option<f64>,
+    pub amount: Option<TokenAmount>,
     pub token_address: Option<String>,
     pub from_address: Option<String>,
     pub to_address: Option<String>,
     pub status: String,
     pub block_number: Option<u64>,
+    // Compute units consumed, once known, and the compute-unit price in
+    // micro-lamports the transaction was sent with
+    pub gas_used: Option<i64>,
+    pub gas_price: Option<BigDecimal>,
     pub metadata: serde_json::Value,
 }
 
+#[derive(Debug, FromRow)]
+pub struct IdempotencyRecord {
+    pub request_hash: String,
+    pub response: Option<serde_json::Value>,
+}
+
+#[derive(Debug, FromRow)]
+pub struct PreparedTransactionRow {
+    pub message: Vec<u8>,
+    pub record: serde_json::Value,
+    pub last_valid_block_height: i64,
+    pub compute_unit_limit: Option<i32>,
+    pub compute_unit_price: Option<i64>,
+}
+
+#[derive(Debug, FromRow)]
+pub struct EventRegistration {
+    pub id: Uuid,
+    pub event_id: Uuid,
+    pub user_id: Uuid,
+    pub event_title: String,
+}
+
+#[derive(Debug, FromRow)]
+pub struct TicketRecord {
+    pub id: Uuid,
+    pub registration_id: Uuid,
+    pub event_id: Uuid,
+    pub user_id: Uuid,
+}
+
+#[derive(Debug, FromRow)]
+pub struct CollectionRecord {
+    pub id: Uuid,
+    pub mint_address: String,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+}
+
+// `owner_address` is the wallet the item was minted to, unknown for items
+// minted elsewhere and verified later; transfers are only visible on chain
+#[derive(Debug, Serialize, FromRow)]
+pub struct CollectionItem {
+    pub mint_address: String,
+    pub owner_address: Option<String>,
+    pub user_id: Option<Uuid>,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub verified: bool,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, FromRow)]
+pub struct AirdropJob {
+    pub id: Uuid,
+    pub mint_address: String,
+    pub created_by: Uuid,
+    pub status: String,
+    pub fees: serde_json::Value,
+}
+
+// Airdrop recipients to put back in the queue: those in flight in one
+// transaction, or pending ones whose transaction could not be built
+#[derive(Debug, Clone, Copy)]
+pub enum AirdropRetry<'a> {
+    InFlight(&'a str),
+    Unbuilt(&'a [Uuid]),
+}
+
+// `transaction_hash` is the last transaction that tried to reach the
+// recipient and `error` why the last failed attempt did not
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct AirdropRecipientRecord {
+    pub id: Uuid,
+    pub position: i32,
+    pub destination: String,
+    pub amount: TokenAmount,
+    pub status: String,
+    pub attempts: i32,
+    pub transaction_hash: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct CampaignRecord {
+    pub id: Uuid,
+    pub name: String,
+    pub mint_address: String,
+    pub distribution: String,
+    pub merkle_root: String,
+    pub total_entries: i32,
+    pub total_amount: TokenAmount,
+    pub is_active: bool,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, FromRow)]
+pub struct CampaignEntryRecord {
+    pub id: Uuid,
+    pub position: i32,
+    pub wallet_address: String,
+    pub amount: TokenAmount,
+    pub proof: serde_json::Value,
+    pub status: String,
+}
+
+// Pending row as seen by the reconciler
+#[derive(Debug, FromRow)]
+pub struct PendingTransaction {
+    pub id: Uuid,
+    pub transaction_hash: String,
+    pub last_valid_block_height: Option<i64>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
 #[derive(Debug, Serialize, FromRow)]
 pub struct MintRecord {
     pub id: Uuid,
     pub mint_address: String,
     pub decimals: i16,
-    pub mint_authority: String,
+    pub mint_authority: Option<String>,
     pub freeze_authority: Option<String>,
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
 
+#[derive(Debug, Serialize)]
+pub struct MintInfoResponse {
+    pub mint_address: String,
+    pub supply: TokenAmount,
+    pub mint_authority: Option<String>,
+    pub freeze_authority: Option<String>,
+    pub is_initialized: bool,
+    pub token_program: TokenProgram,
+    // Present when the mint was created through this service
+    pub record: Option<MintRecord>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TokenAccountsQuery {
+    pub owner: String,
+}
+
 // Token account information
 #[derive(Debug, Serialize)]
 pub struct TokenAccountInfo {
     pub address: String,
     pub mint: String,
     pub owner: String,
-    pub amount: u64,
-    pub decimals: u8,
+    #[serde(flatten)]
+    pub balance: TokenAmount,
+}
+
+// Ledger balance derived from a user's confirmed transactions
+#[derive(Debug, Serialize)]
+pub struct UserTokenBalance {
+    pub mint_address: String,
+    #[serde(flatten)]
+    pub balance: TokenAmount,
 }
 
 // Error types for API responses
@@ -0,0 +1,78 @@
+use crate::{
+    blockchain::{BuiltTransaction, ComputeBudget},
+    database::DatabaseService,
+    error::{AppError, Result},
+    models::{PreparedTransactionRow, TransactionRecord},
+};
+use anyhow::anyhow;
+use solana_sdk::transaction::Transaction;
+use std::{sync::Arc, time::Duration};
+
+// A prepared transaction stops being useful once its blockhash expires
+// (~150 slots), so entries are only kept a little longer than that
+const PREPARED_TTL: Duration = Duration::from_secs(120);
+
+// Transactions handed out for wallet-side signing, keyed by message hash so a
+// submitted transaction can be matched to exactly what was prepared. Entries
+// live in the database, so any replica can accept the signed transaction.
+pub struct PreparedTransactions {
+    database: Arc<DatabaseService>,
+}
+
+impl PreparedTransactions {
+    pub fn new(database: Arc<DatabaseService>) -> Self {
+        Self { database }
+    }
+
+    pub async fn insert(&self, built: &BuiltTransaction, record: TransactionRecord) -> Result<()> {
+        let row = PreparedTransactionRow {
+            message: built.transaction.message_data(),
+            record: serde_json::to_value(&record)
+                .map_err(|e| anyhow!("Failed to serialize prepared transaction record: {}", e))?,
+            last_valid_block_height: built.last_valid_block_height as i64,
+            compute_unit_limit: built.compute_budget.compute_unit_limit.map(|limit| limit as i32),
+            compute_unit_price: built.compute_budget.compute_unit_price.map(|price| price as i64),
+        };
+
+        self.database
+            .insert_prepared_transaction(&message_hash(&built.transaction), &row, PREPARED_TTL)
+            .await
+    }
+
+    // Looks up the record for a wallet-signed transaction without consuming
+    // it, and rebuilds the transaction with what it was prepared with
+    pub async fn get(&self, transaction: Transaction) -> Result<(TransactionRecord, BuiltTransaction)> {
+        let row = self
+            .database
+            .get_prepared_transaction(&message_hash(&transaction))
+            .await?
+            .filter(|row| row.message == transaction.message_data())
+            .ok_or_else(|| {
+                AppError::InvalidInput(
+                    "Transaction does not match a prepared transaction or has expired".to_string(),
+                )
+            })?;
+
+        let record: TransactionRecord = serde_json::from_value(row.record)
+            .map_err(|e| anyhow!("Stored prepared transaction record is malformed: {}", e))?;
+        let compute_budget = ComputeBudget {
+            compute_unit_limit: row.compute_unit_limit.map(|limit| limit as u32),
+            compute_unit_price: row.compute_unit_price.map(|price| price as u64),
+        };
+
+        Ok((
+            record,
+            BuiltTransaction::new(transaction, row.last_valid_block_height as u64, compute_budget),
+        ))
+    }
+
+    pub async fn remove(&self, transaction: &Transaction) -> Result<()> {
+        self.database
+            .delete_prepared_transaction(&message_hash(transaction))
+            .await
+    }
+}
+
+fn message_hash(transaction: &Transaction) -> String {
+    transaction.message.hash().to_string()
+}
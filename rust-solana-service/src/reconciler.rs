@@ -0,0 +1,155 @@
+use crate::{blockchain::env_parse, error::Result, AppState};
+use solana_sdk::signature::Signature;
+use std::{str::FromStr, time::Duration};
+use tracing::{info, warn};
+
+// Rows recorded before `last_valid_block_height` was tracked fall back to an
+// age check; a blockhash is never accepted for longer than this
+const MAX_BLOCKHASH_AGE_SECS: i64 = 150;
+
+pub struct ReconcilerConfig {
+    pub interval: Duration,
+    pub batch_size: i64,
+}
+
+impl ReconcilerConfig {
+    pub fn from_env() -> Self {
+        let interval_secs = env_parse("RECONCILER_INTERVAL_SECS").unwrap_or(30);
+
+        let batch_size = env_parse("RECONCILER_BATCH_SIZE")
+            .filter(|size| *size > 0)
+            .unwrap_or(256);
+
+        Self {
+            interval: Duration::from_secs(interval_secs),
+            batch_size,
+        }
+    }
+}
+
+// Runs a reconciliation pass immediately and then on every interval, moving
+// pending transactions to confirmed or failed as the cluster decides them
+pub fn spawn(state: AppState, config: ReconcilerConfig) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(config.interval);
+
+        loop {
+            ticker.tick().await;
+
+            if let Err(err) = reconcile_pending(&state, config.batch_size).await {
+                warn!("Transaction reconciliation failed: {:?}", err);
+            }
+        }
+    });
+}
+
+async fn reconcile_pending(state: &AppState, batch_size: i64) -> Result<()> {
+    let block_height = state.blockchain.get_block_height().await?;
+    let mut after = None;
+    let mut settled = 0;
+
+    loop {
+        let batch = state
+            .database
+            .get_pending_transactions(after, batch_size)
+            .await?;
+
+        let Some(last) = batch.last() else {
+            break;
+        };
+        after = Some((last.created_at, last.id));
+
+        let signatures: Vec<Signature> = batch
+            .iter()
+            .filter_map(|pending| Signature::from_str(&pending.transaction_hash).ok())
+            .collect();
+        let statuses = state.blockchain.get_signature_statuses(&signatures).await?;
+        let mut expired = Vec::new();
+
+        for (signature, status) in signatures.iter().zip(statuses) {
+            let hash = signature.to_string();
+            let Some(pending) = batch.iter().find(|pending| pending.transaction_hash == hash) else {
+                continue;
+            };
+
+            match status {
+                Some(status) if status.status == "confirmed" || status.status == "failed" => {
+                    state
+                        .database
+                        .record_transaction_outcome(
+                            &hash,
+                            &status.status,
+                            status.slot,
+                            serde_json::json!({ "confirmations": status.confirmations }),
+                        )
+                        .await?;
+                    settled += 1;
+                }
+                // Seen by the cluster but not yet at our commitment level
+                Some(_) => {}
+                None => {
+                    let is_expired = match pending.last_valid_block_height {
+                        Some(last_valid) => block_height > last_valid as u64,
+                        None => {
+                            (chrono::Utc::now() - pending.created_at).num_seconds()
+                                > MAX_BLOCKHASH_AGE_SECS
+                        }
+                    };
+
+                    if is_expired {
+                        expired.push(*signature);
+                    }
+                }
+            }
+        }
+
+        // The status cache only covers recent slots, so a transaction that
+        // landed while the service was down looks unknown there. Failing it
+        // would let callers retry something that already happened.
+        let history = state
+            .blockchain
+            .get_signature_statuses_with_history(&expired)
+            .await?;
+
+        for (signature, status) in expired.iter().zip(history) {
+            let hash = signature.to_string();
+
+            match status {
+                Some(status) if status.status == "confirmed" || status.status == "failed" => {
+                    state
+                        .database
+                        .record_transaction_outcome(
+                            &hash,
+                            &status.status,
+                            status.slot,
+                            serde_json::json!({ "confirmations": status.confirmations }),
+                        )
+                        .await?;
+                }
+                Some(_) => continue,
+                None => {
+                    state
+                        .database
+                        .record_transaction_outcome(
+                            &hash,
+                            "failed",
+                            None,
+                            serde_json::json!({ "failure_reason": "blockhash_expired" }),
+                        )
+                        .await?;
+                }
+            }
+            settled += 1;
+        }
+
+        if (batch.len() as i64) < batch_size {
+            break;
+        }
+    }
+
+    if settled > 0 {
+        info!("Reconciled {} pending transactions", settled);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,367 @@
+use crate::error::Result;
+use sqlx::PgConnection;
+
+// Side effects of a transaction reaching its final status, one function per
+// feature. `settle` runs them inside the database transaction that records
+// the status, and only the first time a row settles. Each function matches
+// its own rows by the transaction's metadata, so a transaction that is not
+// part of the feature leaves it untouched.
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    Confirmed,
+    Failed,
+}
+
+pub async fn settle(
+    conn: &mut PgConnection,
+    transaction_hash: &str,
+    transaction_type: &str,
+    status: &str,
+) -> Result<()> {
+    let outcome = match status {
+        "confirmed" => Outcome::Confirmed,
+        "failed" => Outcome::Failed,
+        _ => return Ok(()),
+    };
+
+    match transaction_type {
+        "mint" => {
+            token_supply(conn, transaction_hash, outcome).await?;
+            collection_item(conn, transaction_hash, outcome).await?;
+            campaign_claim(conn, transaction_hash, outcome).await?;
+            membership(conn, transaction_hash, outcome).await?;
+            ticket_issue(conn, transaction_hash, outcome).await?;
+        }
+        "burn" => token_supply(conn, transaction_hash, outcome).await?,
+        "transfer" => campaign_claim(conn, transaction_hash, outcome).await?,
+        "set_authority" => mint_authority(conn, transaction_hash, outcome).await?,
+        "update_metadata" => token_metadata(conn, transaction_hash, outcome).await?,
+        "verify_collection" => collection_item(conn, transaction_hash, outcome).await?,
+        "freeze" => ticket_check_in(conn, transaction_hash, outcome).await?,
+        _ => {}
+    }
+
+    Ok(())
+}
+
+// Confirmed mints and burns move the mint's recorded supply
+async fn token_supply(conn: &mut PgConnection, transaction_hash: &str, outcome: Outcome) -> Result<()> {
+    if outcome != Outcome::Confirmed {
+        return Ok(());
+    }
+
+    sqlx::query!(
+        r#"
+        UPDATE token_mints tm
+        SET total_supply = COALESCE(tm.total_supply, 0) + CASE
+                WHEN bt.transaction_type = 'mint' THEN bt.amount
+                ELSE -bt.amount
+            END,
+            updated_at = NOW()
+        FROM blockchain_transactions bt
+        WHERE bt.transaction_hash = $1
+          AND bt.amount IS NOT NULL
+          AND tm.mint_address = bt.token_address
+        "#,
+        transaction_hash
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+// Keeps `token_mints` authorities, and their history, in step with the chain
+async fn mint_authority(conn: &mut PgConnection, transaction_hash: &str, outcome: Outcome) -> Result<()> {
+    if outcome != Outcome::Confirmed {
+        return Ok(());
+    }
+
+    sqlx::query!(
+        r#"
+        UPDATE token_mints tm
+        SET mint_authority = CASE
+                WHEN bt.metadata->>'authority_type' = 'mint' THEN bt.metadata->>'new_authority'
+                ELSE tm.mint_authority
+            END,
+            freeze_authority = CASE
+                WHEN bt.metadata->>'authority_type' = 'freeze' THEN bt.metadata->>'new_authority'
+                ELSE tm.freeze_authority
+            END,
+            metadata = jsonb_set(
+                COALESCE(tm.metadata, '{}'::JSONB),
+                '{authority_history}',
+                COALESCE(tm.metadata->'authority_history', '[]'::JSONB) || jsonb_build_array(
+                    jsonb_build_object(
+                        'authority_type', bt.metadata->>'authority_type',
+                        'previous_authority', bt.metadata->'current_authority',
+                        'new_authority', bt.metadata->'new_authority',
+                        'signature', bt.transaction_hash,
+                        'changed_at', NOW()
+                    )
+                )
+            ),
+            updated_at = NOW()
+        FROM blockchain_transactions bt
+        WHERE bt.transaction_hash = $1
+          AND tm.mint_address = bt.token_address
+        "#,
+        transaction_hash
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+// Mirrors confirmed metadata updates into `token_mints.metadata`
+async fn token_metadata(conn: &mut PgConnection, transaction_hash: &str, outcome: Outcome) -> Result<()> {
+    if outcome != Outcome::Confirmed {
+        return Ok(());
+    }
+
+    sqlx::query!(
+        r#"
+        UPDATE token_mints tm
+        SET metadata = COALESCE(tm.metadata, '{}'::JSONB) || jsonb_build_object(
+                'name', bt.metadata->'name',
+                'symbol', bt.metadata->'symbol',
+                'uri', bt.metadata->'uri'
+            ),
+            updated_at = NOW()
+        FROM blockchain_transactions bt
+        WHERE bt.transaction_hash = $1
+          AND tm.mint_address = bt.token_address
+        "#,
+        transaction_hash
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+// Collection items are minted and verified in one transaction, or verified
+// by a later one; either way the item's row carries it. The row keeps the
+// signature it was stored with, so follow `replaces` back from a rebuilt
+// transaction to find it.
+async fn collection_item(conn: &mut PgConnection, transaction_hash: &str, outcome: Outcome) -> Result<()> {
+    if outcome != Outcome::Confirmed {
+        return Ok(());
+    }
+
+    sqlx::query!(
+        r#"
+        WITH RECURSIVE chain(transaction_hash) AS (
+            SELECT $1::TEXT
+            UNION
+            SELECT bt.metadata->>'replaces'
+            FROM blockchain_transactions bt
+            JOIN chain ON bt.transaction_hash = chain.transaction_hash
+            WHERE bt.metadata ? 'replaces'
+        )
+        UPDATE nft_collection_items SET verified = TRUE, transaction_hash = $1
+        WHERE transaction_hash IN (SELECT transaction_hash FROM chain)
+        "#,
+        transaction_hash
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+// Campaign claims settle with their payout, so an entry whose claim ended
+// without an answer does not stay in `claiming`. A failed claim reopens the
+// entry only if no rebuilt or later claim transaction may still pay it.
+async fn campaign_claim(conn: &mut PgConnection, transaction_hash: &str, outcome: Outcome) -> Result<()> {
+    match outcome {
+        Outcome::Confirmed => {
+            sqlx::query!(
+                r#"
+                UPDATE airdrop_campaign_entries e
+                SET status = 'claimed',
+                    claim_signature = bt.transaction_hash,
+                    claimed_at = COALESCE(e.claimed_at, NOW()),
+                    updated_at = NOW()
+                FROM blockchain_transactions bt
+                WHERE bt.transaction_hash = $1
+                  AND e.campaign_id = (bt.metadata->>'campaign_id')::UUID
+                  AND e.position = (bt.metadata->>'campaign_index')::INTEGER
+                "#,
+                transaction_hash
+            )
+            .execute(&mut *conn)
+            .await?;
+        }
+        Outcome::Failed => {
+            sqlx::query!(
+                r#"
+                UPDATE airdrop_campaign_entries e
+                SET status = 'unclaimed', updated_at = NOW()
+                FROM blockchain_transactions bt
+                WHERE bt.transaction_hash = $1
+                  AND NOT bt.metadata ? 'superseded_by'
+                  AND e.campaign_id = (bt.metadata->>'campaign_id')::UUID
+                  AND e.position = (bt.metadata->>'campaign_index')::INTEGER
+                  AND e.status = 'claiming'
+                  AND NOT EXISTS (
+                      SELECT 1 FROM blockchain_transactions other
+                      WHERE other.metadata->>'campaign_id' = bt.metadata->>'campaign_id'
+                        AND other.metadata->>'campaign_index' = bt.metadata->>'campaign_index'
+                        AND other.status <> 'failed'
+                  )
+                "#,
+                transaction_hash
+            )
+            .execute(&mut *conn)
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+// Membership badges are claimed before they are minted, so one whose mint
+// ended without an answer does not keep holding the user's badge slot
+async fn membership(conn: &mut PgConnection, transaction_hash: &str, outcome: Outcome) -> Result<()> {
+    match outcome {
+        Outcome::Confirmed => {
+            sqlx::query!(
+                r#"
+                UPDATE token_memberships m
+                SET transaction_hash = bt.transaction_hash
+                FROM blockchain_transactions bt
+                WHERE bt.transaction_hash = $1
+                  AND bt.metadata ? 'membership'
+                  AND m.user_id = bt.user_id
+                  AND m.mint_address = bt.token_address
+                "#,
+                transaction_hash
+            )
+            .execute(&mut *conn)
+            .await?;
+        }
+        Outcome::Failed => {
+            sqlx::query!(
+                r#"
+                DELETE FROM token_memberships m
+                USING blockchain_transactions bt
+                WHERE bt.transaction_hash = $1
+                  AND bt.metadata ? 'membership'
+                  AND NOT bt.metadata ? 'superseded_by'
+                  AND m.user_id = bt.user_id
+                  AND m.mint_address = bt.token_address
+                  AND m.transaction_hash IS NULL
+                  AND NOT EXISTS (
+                      SELECT 1 FROM blockchain_transactions other
+                      WHERE other.metadata ? 'membership'
+                        AND other.user_id = bt.user_id
+                        AND other.token_address = bt.token_address
+                        AND other.status <> 'failed'
+                  )
+                "#,
+                transaction_hash
+            )
+            .execute(&mut *conn)
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+// Tickets move to `issuing` before their mint is sent. Each issue attempt
+// mints a fresh NFT, so the mint address ties the ticket row to this attempt.
+async fn ticket_issue(conn: &mut PgConnection, transaction_hash: &str, outcome: Outcome) -> Result<()> {
+    match outcome {
+        Outcome::Confirmed => {
+            sqlx::query!(
+                r#"
+                UPDATE event_tickets t
+                SET status = 'issued', issue_signature = bt.transaction_hash, updated_at = NOW()
+                FROM blockchain_transactions bt
+                WHERE bt.transaction_hash = $1
+                  AND bt.metadata ? 'ticket'
+                  AND t.registration_id = (bt.metadata->>'registration_id')::UUID
+                  AND t.mint_address = bt.token_address
+                  AND t.status = 'issuing'
+                "#,
+                transaction_hash
+            )
+            .execute(&mut *conn)
+            .await?;
+        }
+        Outcome::Failed => {
+            sqlx::query!(
+                r#"
+                DELETE FROM event_tickets t
+                USING blockchain_transactions bt
+                WHERE bt.transaction_hash = $1
+                  AND bt.metadata ? 'ticket'
+                  AND NOT bt.metadata ? 'superseded_by'
+                  AND t.registration_id = (bt.metadata->>'registration_id')::UUID
+                  AND t.mint_address = bt.token_address
+                  AND t.status = 'issuing'
+                "#,
+                transaction_hash
+            )
+            .execute(&mut *conn)
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+// Tickets move to `redeeming` before the check-in freeze is sent
+async fn ticket_check_in(conn: &mut PgConnection, transaction_hash: &str, outcome: Outcome) -> Result<()> {
+    match outcome {
+        Outcome::Confirmed => {
+            sqlx::query!(
+                r#"
+                UPDATE event_tickets t
+                SET status = 'redeemed',
+                    redeem_signature = bt.transaction_hash,
+                    redeemed_at = NOW(),
+                    updated_at = NOW()
+                FROM blockchain_transactions bt
+                WHERE bt.transaction_hash = $1
+                  AND bt.metadata->>'reason' = 'ticket_check_in'
+                  AND t.mint_address = bt.token_address
+                  AND t.status = 'redeeming'
+                "#,
+                transaction_hash
+            )
+            .execute(&mut *conn)
+            .await?;
+        }
+        Outcome::Failed => {
+            sqlx::query!(
+                r#"
+                UPDATE event_tickets t
+                SET status = 'issued', redeemed_wallet = NULL, updated_at = NOW()
+                FROM blockchain_transactions bt
+                WHERE bt.transaction_hash = $1
+                  AND bt.metadata->>'reason' = 'ticket_check_in'
+                  AND NOT bt.metadata ? 'superseded_by'
+                  AND t.mint_address = bt.token_address
+                  AND t.status = 'redeeming'
+                  AND NOT EXISTS (
+                      SELECT 1 FROM blockchain_transactions other
+                      WHERE other.transaction_type = 'freeze'
+                        AND other.metadata->>'reason' = 'ticket_check_in'
+                        AND other.token_address = bt.token_address
+                        AND other.status <> 'failed'
+                  )
+                "#,
+                transaction_hash
+            )
+            .execute(&mut *conn)
+            .await?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,15 @@
+-- Idempotency keys for mint and transfer requests from the Solana service
+CREATE TABLE IF NOT EXISTS idempotency_keys (
+  endpoint TEXT NOT NULL,
+  idempotency_key TEXT NOT NULL,
+  request_hash TEXT NOT NULL,
+  response JSONB,
+  created_at TIMESTAMPTZ DEFAULT NOW(),
+  completed_at TIMESTAMPTZ,
+  PRIMARY KEY (endpoint, idempotency_key)
+);
+
+CREATE INDEX IF NOT EXISTS idx_idempotency_keys_created_at ON idempotency_keys(created_at);
+
+-- Only the service role touches this table
+ALTER TABLE idempotency_keys ENABLE ROW LEVEL SECURITY;
@@ -0,0 +1,10 @@
+-- A revoked mint authority is stored as NULL (fixed supply)
+ALTER TABLE token_mints ALTER COLUMN mint_authority DROP NOT NULL;
+
+-- Allow authority changes to be recorded as blockchain transactions
+ALTER TABLE blockchain_transactions
+  DROP CONSTRAINT IF EXISTS blockchain_transactions_transaction_type_check;
+
+ALTER TABLE blockchain_transactions
+  ADD CONSTRAINT blockchain_transactions_transaction_type_check
+  CHECK (transaction_type IN ('mint', 'transfer', 'burn', 'stake', 'freeze', 'thaw', 'set_authority'));
@@ -0,0 +1,7 @@
+-- Allow native SOL transfers to be recorded as blockchain transactions
+ALTER TABLE blockchain_transactions
+  DROP CONSTRAINT IF EXISTS blockchain_transactions_transaction_type_check;
+
+ALTER TABLE blockchain_transactions
+  ADD CONSTRAINT blockchain_transactions_transaction_type_check
+  CHECK (transaction_type IN ('mint', 'transfer', 'burn', 'stake', 'freeze', 'thaw', 'set_authority', 'sol_transfer'));
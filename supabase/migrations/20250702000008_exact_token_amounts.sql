@@ -0,0 +1,45 @@
+-- Token amounts are stored in UI units with the mint's full precision.
+-- DECIMAL(20,8) could not hold amounts of 9-decimal mints, so use NUMERIC.
+ALTER TABLE blockchain_transactions ALTER COLUMN amount TYPE NUMERIC;
+ALTER TABLE token_mints ALTER COLUMN total_supply TYPE NUMERIC;
+ALTER TABLE token_holdings ALTER COLUMN balance TYPE NUMERIC;
+
+-- Earlier rows stored raw base units in `amount`; rewrite them in UI units
+-- from the exact raw value kept in metadata, scaled to the mint's decimals
+UPDATE blockchain_transactions bt
+SET amount = ROUND((bt.metadata->>'amount')::NUMERIC / (10::NUMERIC ^ tm.decimals), tm.decimals)
+FROM token_mints tm
+WHERE tm.mint_address = bt.token_address
+  AND bt.transaction_type IN ('mint', 'transfer', 'burn')
+  AND bt.metadata ? 'amount';
+
+-- Without a token_mints row the decimals are unknown, so the amount cannot
+-- be rescaled. Clear it rather than leave base units that would read as UI
+-- units; the raw value stays in metadata.
+DO $$
+DECLARE
+  unscaled INTEGER;
+BEGIN
+  UPDATE blockchain_transactions bt
+  SET amount = NULL
+  WHERE bt.transaction_type IN ('mint', 'transfer', 'burn')
+    AND bt.metadata ? 'amount'
+    AND bt.amount IS NOT NULL
+    AND NOT EXISTS (
+      SELECT 1 FROM token_mints tm WHERE tm.mint_address = bt.token_address
+    );
+
+  GET DIAGNOSTICS unscaled = ROW_COUNT;
+  IF unscaled > 0 THEN
+    RAISE NOTICE 'Cleared amount of % transactions whose mint has no token_mints row', unscaled;
+  END IF;
+END $$;
+
+UPDATE blockchain_transactions
+SET amount = ROUND((metadata->>'amount_lamports')::NUMERIC / 1000000000, 9)
+WHERE transaction_type = 'sol_transfer'
+  AND metadata ? 'amount_lamports';
+
+UPDATE token_mints
+SET total_supply = ROUND(total_supply / (10::NUMERIC ^ decimals), decimals)
+WHERE total_supply IS NOT NULL;
@@ -0,0 +1,18 @@
+-- Soulbound membership badges: one per user and badge mint. The row is
+-- claimed before the badge is minted so concurrent requests cannot both mint.
+CREATE TABLE IF NOT EXISTS token_memberships (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  user_id UUID NOT NULL REFERENCES user_profiles(id) ON DELETE CASCADE,
+  mint_address TEXT NOT NULL REFERENCES token_mints(mint_address) ON DELETE CASCADE,
+  wallet_address TEXT NOT NULL,
+  transaction_hash TEXT,
+  created_at TIMESTAMPTZ DEFAULT NOW(),
+  UNIQUE(user_id, mint_address)
+);
+
+CREATE INDEX IF NOT EXISTS idx_token_memberships_user_id ON token_memberships(user_id);
+
+ALTER TABLE token_memberships ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY "Users can view their own memberships" ON token_memberships
+  FOR SELECT USING (auth.uid() = user_id);
@@ -0,0 +1,35 @@
+-- NFT tickets issued for event registrations. A ticket is redeemed at
+-- check-in by freezing the holder's token account.
+CREATE TABLE IF NOT EXISTS event_tickets (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  registration_id UUID NOT NULL UNIQUE REFERENCES event_registrations(id) ON DELETE CASCADE,
+  event_id UUID NOT NULL REFERENCES events(id) ON DELETE CASCADE,
+  user_id UUID NOT NULL REFERENCES user_profiles(id) ON DELETE CASCADE,
+  mint_address TEXT UNIQUE,
+  wallet_address TEXT NOT NULL,
+  status TEXT NOT NULL DEFAULT 'issuing' CHECK (status IN ('issuing', 'issued', 'redeeming', 'redeemed')),
+  issue_signature TEXT,
+  redeem_signature TEXT,
+  redeemed_wallet TEXT,
+  redeemed_at TIMESTAMPTZ,
+  created_at TIMESTAMPTZ DEFAULT NOW(),
+  updated_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_event_tickets_event_id ON event_tickets(event_id);
+CREATE INDEX IF NOT EXISTS idx_event_tickets_user_id ON event_tickets(user_id);
+
+CREATE TRIGGER update_event_tickets_updated_at BEFORE UPDATE ON event_tickets FOR EACH ROW EXECUTE PROCEDURE update_updated_at_column();
+
+ALTER TABLE event_tickets ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY "Users can view their own tickets" ON event_tickets
+  FOR SELECT USING (auth.uid() = user_id);
+
+CREATE POLICY "Admins can view all tickets" ON event_tickets
+  FOR SELECT USING (
+    EXISTS (
+      SELECT 1 FROM user_profiles 
+      WHERE id = auth.uid() AND role = 'admin'
+    )
+  );
@@ -0,0 +1,47 @@
+-- Metaplex NFT collections administered by the service, and the items
+-- verified into them. Collection and item mints also have token_mints rows.
+CREATE TABLE IF NOT EXISTS nft_collections (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  mint_address TEXT NOT NULL UNIQUE,
+  name TEXT NOT NULL,
+  symbol TEXT NOT NULL,
+  uri TEXT NOT NULL,
+  created_by UUID REFERENCES user_profiles(id) ON DELETE SET NULL,
+  created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+-- `verified` is set once the transaction verifying the item confirms
+CREATE TABLE IF NOT EXISTS nft_collection_items (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  collection_id UUID NOT NULL REFERENCES nft_collections(id) ON DELETE CASCADE,
+  mint_address TEXT NOT NULL UNIQUE,
+  owner_address TEXT,
+  user_id UUID REFERENCES user_profiles(id) ON DELETE SET NULL,
+  name TEXT NOT NULL,
+  symbol TEXT NOT NULL,
+  uri TEXT NOT NULL,
+  verified BOOLEAN NOT NULL DEFAULT FALSE,
+  transaction_hash TEXT NOT NULL,
+  created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_nft_collection_items_collection_id ON nft_collection_items(collection_id);
+CREATE INDEX IF NOT EXISTS idx_nft_collection_items_user_id ON nft_collection_items(user_id);
+CREATE INDEX IF NOT EXISTS idx_nft_collection_items_transaction_hash ON nft_collection_items(transaction_hash);
+
+ALTER TABLE nft_collections ENABLE ROW LEVEL SECURITY;
+ALTER TABLE nft_collection_items ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY "Anyone can view collections" ON nft_collections
+  FOR SELECT USING (true);
+
+CREATE POLICY "Anyone can view collection items" ON nft_collection_items
+  FOR SELECT USING (true);
+
+-- Allow verifying an existing NFT into a collection to be recorded
+ALTER TABLE blockchain_transactions
+  DROP CONSTRAINT IF EXISTS blockchain_transactions_transaction_type_check;
+
+ALTER TABLE blockchain_transactions
+  ADD CONSTRAINT blockchain_transactions_transaction_type_check
+  CHECK (transaction_type IN ('mint', 'transfer', 'burn', 'stake', 'freeze', 'thaw', 'set_authority', 'sol_transfer', 'update_metadata', 'verify_collection'));
@@ -0,0 +1,60 @@
+-- Batch mints to many recipients of one mint. Recipients are packed into as
+-- few transactions as fit and their progress is kept here, so a job that was
+-- interrupted resumes where it stopped.
+CREATE TABLE IF NOT EXISTS airdrop_jobs (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  mint_address TEXT NOT NULL,
+  decimals SMALLINT NOT NULL,
+  created_by UUID NOT NULL REFERENCES user_profiles(id) ON DELETE CASCADE,
+  status TEXT NOT NULL DEFAULT 'running' CHECK (status IN ('running', 'completed')),
+  -- Priority fee options applied to every transaction of the job
+  fees JSONB NOT NULL DEFAULT '{}'::JSONB,
+  total_recipients INTEGER NOT NULL,
+  total_amount NUMERIC NOT NULL,
+  created_at TIMESTAMPTZ DEFAULT NOW(),
+  updated_at TIMESTAMPTZ DEFAULT NOW(),
+  completed_at TIMESTAMPTZ
+);
+
+-- A recipient is `sending` while the transaction in `transaction_hash` is
+-- unsettled; it goes back to `pending` if that transaction fails, and to
+-- `failed` once it has used up its attempts
+CREATE TABLE IF NOT EXISTS airdrop_recipients (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  job_id UUID NOT NULL REFERENCES airdrop_jobs(id) ON DELETE CASCADE,
+  position INTEGER NOT NULL,
+  destination TEXT NOT NULL,
+  amount NUMERIC NOT NULL,
+  status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'sending', 'confirmed', 'failed')),
+  attempts INTEGER NOT NULL DEFAULT 0,
+  transaction_hash TEXT,
+  error TEXT,
+  updated_at TIMESTAMPTZ DEFAULT NOW(),
+  UNIQUE (job_id, position)
+);
+
+CREATE INDEX IF NOT EXISTS idx_airdrop_jobs_status ON airdrop_jobs(status);
+CREATE INDEX IF NOT EXISTS idx_airdrop_recipients_job_status ON airdrop_recipients(job_id, status);
+CREATE INDEX IF NOT EXISTS idx_airdrop_recipients_transaction_hash ON airdrop_recipients(transaction_hash);
+
+CREATE TRIGGER update_airdrop_jobs_updated_at BEFORE UPDATE ON airdrop_jobs FOR EACH ROW EXECUTE PROCEDURE update_updated_at_column();
+CREATE TRIGGER update_airdrop_recipients_updated_at BEFORE UPDATE ON airdrop_recipients FOR EACH ROW EXECUTE PROCEDURE update_updated_at_column();
+
+ALTER TABLE airdrop_jobs ENABLE ROW LEVEL SECURITY;
+ALTER TABLE airdrop_recipients ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY "Admins can view airdrop jobs" ON airdrop_jobs
+  FOR SELECT USING (
+    EXISTS (
+      SELECT 1 FROM user_profiles 
+      WHERE id = auth.uid() AND role = 'admin'
+    )
+  );
+
+CREATE POLICY "Admins can view airdrop recipients" ON airdrop_recipients
+  FOR SELECT USING (
+    EXISTS (
+      SELECT 1 FROM user_profiles 
+      WHERE id = auth.uid() AND role = 'admin'
+    )
+  );
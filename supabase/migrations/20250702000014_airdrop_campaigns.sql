@@ -0,0 +1,60 @@
+-- Claimable airdrops. Each campaign commits to its (wallet, amount) entries
+-- with a Merkle root; entries keep their proof and are paid out only when
+-- claimed, so no rent is spent on wallets that never claim.
+CREATE TABLE IF NOT EXISTS airdrop_campaigns (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  name TEXT NOT NULL,
+  mint_address TEXT NOT NULL,
+  distribution TEXT NOT NULL CHECK (distribution IN ('mint', 'transfer')),
+  merkle_root TEXT NOT NULL,
+  total_entries INTEGER NOT NULL,
+  total_amount NUMERIC NOT NULL,
+  is_active BOOLEAN NOT NULL DEFAULT TRUE,
+  created_by UUID NOT NULL REFERENCES user_profiles(id) ON DELETE CASCADE,
+  created_at TIMESTAMPTZ DEFAULT NOW(),
+  updated_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+-- `position` is the entry's leaf index and `proof` its sibling hashes from
+-- leaf to root, base58 encoded
+CREATE TABLE IF NOT EXISTS airdrop_campaign_entries (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  campaign_id UUID NOT NULL REFERENCES airdrop_campaigns(id) ON DELETE CASCADE,
+  position INTEGER NOT NULL,
+  wallet_address TEXT NOT NULL,
+  amount NUMERIC NOT NULL,
+  proof JSONB NOT NULL,
+  status TEXT NOT NULL DEFAULT 'unclaimed' CHECK (status IN ('unclaimed', 'claiming', 'claimed')),
+  claim_signature TEXT,
+  claimed_at TIMESTAMPTZ,
+  updated_at TIMESTAMPTZ DEFAULT NOW(),
+  UNIQUE (campaign_id, wallet_address),
+  UNIQUE (campaign_id, position)
+);
+
+CREATE INDEX IF NOT EXISTS idx_airdrop_campaign_entries_wallet ON airdrop_campaign_entries(wallet_address);
+
+CREATE TRIGGER update_airdrop_campaigns_updated_at BEFORE UPDATE ON airdrop_campaigns FOR EACH ROW EXECUTE PROCEDURE update_updated_at_column();
+CREATE TRIGGER update_airdrop_campaign_entries_updated_at BEFORE UPDATE ON airdrop_campaign_entries FOR EACH ROW EXECUTE PROCEDURE update_updated_at_column();
+
+ALTER TABLE airdrop_campaigns ENABLE ROW LEVEL SECURITY;
+ALTER TABLE airdrop_campaign_entries ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY "Anyone can view airdrop campaigns" ON airdrop_campaigns
+  FOR SELECT USING (true);
+
+CREATE POLICY "Users can view entries for their wallets" ON airdrop_campaign_entries
+  FOR SELECT USING (
+    EXISTS (
+      SELECT 1 FROM user_wallets
+      WHERE user_id = auth.uid() AND wallet_address = airdrop_campaign_entries.wallet_address
+    )
+  );
+
+CREATE POLICY "Admins can view all airdrop campaign entries" ON airdrop_campaign_entries
+  FOR SELECT USING (
+    EXISTS (
+      SELECT 1 FROM user_profiles 
+      WHERE id = auth.uid() AND role = 'admin'
+    )
+  );
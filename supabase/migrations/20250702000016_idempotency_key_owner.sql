@@ -0,0 +1,11 @@
+-- Idempotency keys are scoped to the user who sent them, so one user's key
+-- can neither replay nor reveal another user's request. Keys stored before
+-- this have no known owner and are assigned the nil UUID, which no caller
+-- has, so they simply stop matching.
+ALTER TABLE idempotency_keys
+  ADD COLUMN IF NOT EXISTS user_id UUID NOT NULL DEFAULT '00000000-0000-0000-0000-000000000000';
+
+ALTER TABLE idempotency_keys ALTER COLUMN user_id DROP DEFAULT;
+
+ALTER TABLE idempotency_keys DROP CONSTRAINT IF EXISTS idempotency_keys_pkey;
+ALTER TABLE idempotency_keys ADD PRIMARY KEY (user_id, endpoint, idempotency_key);
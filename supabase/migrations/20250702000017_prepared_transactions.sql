@@ -0,0 +1,19 @@
+-- Transactions handed out for wallet-side signing, kept until the wallet
+-- submits them or their blockhash expires. Stored here rather than in the
+-- service's memory so a submission can land on any replica, or after a
+-- restart.
+CREATE TABLE IF NOT EXISTS prepared_transactions (
+  message_hash TEXT PRIMARY KEY,
+  message BYTEA NOT NULL,
+  record JSONB NOT NULL,
+  last_valid_block_height BIGINT NOT NULL,
+  compute_unit_limit INTEGER,
+  compute_unit_price BIGINT,
+  created_at TIMESTAMPTZ DEFAULT NOW(),
+  expires_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_prepared_transactions_expires_at ON prepared_transactions(expires_at);
+
+-- Only the service role touches this table
+ALTER TABLE prepared_transactions ENABLE ROW LEVEL SECURITY;